@@ -20,7 +20,8 @@
 //! Therefore, sharing one client among multiple tasks ensures optimal performance, reduces resource
 //! consumption, and enhances overall software scalability.
 use std::{
-    collections::{hash_map::Entry, HashMap},
+    borrow::Cow,
+    collections::{hash_map::Entry, HashMap, VecDeque},
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -29,7 +30,10 @@ use std::{
 };
 
 use async_trait::async_trait;
-use futures03::{stream::SplitSink, SinkExt, StreamExt};
+use futures03::{
+    stream::{self, BoxStream, SplitSink},
+    SinkExt, StreamExt,
+};
 use hyper::{
     header::{
         AUTHORIZATION, CONNECTION, HOST, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_VERSION, UPGRADE,
@@ -43,11 +47,12 @@ use thiserror::Error;
 use tokio::{
     net::TcpStream,
     sync::{
+        broadcast,
         mpsc::{self, error::TrySendError, Receiver, Sender},
         oneshot, Mutex, MutexGuard, Notify,
     },
     task::JoinHandle,
-    time::sleep,
+    time::{interval_at, sleep, Instant, MissedTickBehavior},
 };
 use tokio_tungstenite::{
     connect_async,
@@ -96,6 +101,10 @@ pub enum DeltasError {
     #[error("The client is not connected!")]
     NotConnected,
 
+    /// The requested subscription is not known to this client.
+    #[error("Subscription {0} not found")]
+    SubscriptionNotFound(Uuid),
+
     /// The connect method was called while the client already had an active connection.
     #[error("The client is already connected!")]
     AlreadyConnected,
@@ -108,6 +117,16 @@ pub enum DeltasError {
     #[error("Connection error: {0}")]
     ConnectionError(#[from] Box<tungstenite::Error>),
 
+    /// A message for a subscription could not be decoded. Carries the (truncated) raw message.
+    #[error("Failed to decode message: {error}. Message: {message}")]
+    DecodeError { error: String, message: String },
+
+    /// The connection was lost and the subscription was re-established on a new connection.
+    /// Messages emitted while disconnected were missed, so the subscriber has to resync its
+    /// state, e.g. from a new snapshot.
+    #[error("Subscription resumed after a reconnect, messages may have been missed")]
+    StreamGap,
+
     /// A fatal error occurred that cannot be recovered from.
     #[error("Tycho FatalError: {0}")]
     Fatal(String),
@@ -139,6 +158,26 @@ impl SubscriptionOptions {
     }
 }
 
+/// Connection lifecycle events emitted by [`WsDeltasClient`].
+///
+/// See [`WsDeltasClient::connection_events`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// A websocket connection to the server was established.
+    Connected,
+    /// The websocket connection was closed or lost.
+    Disconnected,
+    /// The client is about to attempt a reconnect.
+    Reconnecting { attempt: u64 },
+    /// The server confirmed a new subscription.
+    SubscriptionAdded { extractor_id: ExtractorIdentity, subscription_id: Uuid },
+    /// A subscription of a lost connection was re-established after reconnecting.
+    SubscriptionResumed { extractor_id: ExtractorIdentity, subscription_id: Uuid },
+    /// A subscription ended, either on request, by the server or because it could not be resumed
+    /// after the connection was lost.
+    SubscriptionEnded { subscription_id: Uuid },
+}
+
 #[cfg_attr(test, automock)]
 #[async_trait]
 pub trait DeltasClient {
@@ -148,11 +187,15 @@ pub trait DeltasClient {
     /// cancels while waiting for confirmation the subscription may still be registered. If the
     /// receiver was deallocated though, the first message from the subscription will remove it
     /// again - since there is no one to inform about these messages.
+    ///
+    /// If the connection is lost, the subscription is re-established once the client reconnects
+    /// and the receiver keeps working. Since messages emitted while disconnected are missed, the
+    /// receiver then yields a [`DeltasError::StreamGap`] before any new message.
     async fn subscribe(
         &self,
         extractor_id: ExtractorIdentity,
         options: SubscriptionOptions,
-    ) -> Result<(Uuid, Receiver<BlockChanges>), DeltasError>;
+    ) -> Result<(Uuid, Receiver<Result<BlockChanges, DeltasError>>), DeltasError>;
 
     /// Unsubscribe from an subscription
     async fn unsubscribe(&self, subscription_id: Uuid) -> Result<(), DeltasError>;
@@ -164,16 +207,54 @@ pub trait DeltasClient {
     async fn close(&self) -> Result<(), DeltasError>;
 }
 
+/// Default interval between heartbeat pings sent to the server.
+const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(10);
+/// Default duration without any frame from the server after which the connection is considered
+/// dead. The server itself pings every 5 seconds.
+const DEFAULT_HEARTBEAT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Number of connection events buffered for each listener before the oldest ones are dropped.
+const CONNECTION_EVENTS_BUFFER_SIZE: usize = 64;
+
+/// Maximum number of bytes of a raw message that are included in logs.
+const MAX_LOGGED_MESSAGE_LEN: usize = 1024;
+
+/// Truncates a raw message so it can be logged without flooding the logs.
+fn truncate_for_log(msg: &str) -> Cow<'_, str> {
+    if msg.len() <= MAX_LOGGED_MESSAGE_LEN {
+        return Cow::Borrowed(msg)
+    }
+    let mut end = MAX_LOGGED_MESSAGE_LEN;
+    while !msg.is_char_boundary(end) {
+        end -= 1;
+    }
+    Cow::Owned(format!("{}... ({} bytes truncated)", &msg[..end], msg.len() - end))
+}
+
 #[derive(Clone)]
 pub struct WsDeltasClient {
     /// The tycho indexer websocket uri.
     uri: Uri,
     /// Authorization key for the websocket connection.
     auth_key: Option<String>,
+    /// Server api version, used as the first path segment of the websocket endpoint.
+    server_version: String,
+    /// Path of the websocket endpoint, relative to the server version.
+    ws_path: String,
+    /// Additional headers sent with the websocket handshake request.
+    headers: Vec<(String, String)>,
     /// Maximum amount of reconnects to try before giving up.
     max_reconnects: u64,
     /// Duration to wait before attempting to reconnect
     retry_cooldown: Duration,
+    /// Upper bound for the reconnect cooldown. The cooldown doubles on each consecutive attempt
+    /// until it reaches this value. If equal to `retry_cooldown` the cooldown stays constant.
+    max_retry_cooldown: Duration,
+    /// How often to ping the server to keep the connection alive.
+    heartbeat_interval: Duration,
+    /// If no frame is received from the server for this long, the connection is considered dead
+    /// and a reconnect is attempted.
+    heartbeat_timeout: Duration,
     /// The client will buffer this many messages incoming from the websocket
     /// before starting to drop them.
     ws_buffer_size: usize,
@@ -186,11 +267,35 @@ pub struct WsDeltasClient {
     inner: Arc<Mutex<Option<Inner>>>,
     /// If set the client has exhausted its reconnection attempts
     dead: Arc<AtomicBool>,
+    /// Broadcasts connection lifecycle events to any listeners.
+    events: broadcast::Sender<ConnectionEvent>,
+    /// Subscriptions of a lost connection, waiting to be re-established once reconnected.
+    resumable: Arc<Mutex<ResumableSubscriptions>>,
 }
 
 type WebSocketSink =
     SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, tungstenite::protocol::Message>;
 
+/// Result of a subscription request: the subscription id and the receiver for its messages.
+type SubscriptionResult = Result<(Uuid, Receiver<Result<BlockChanges, DeltasError>>), DeltasError>;
+
+/// Subscriptions waiting to be re-established, grouped by extractor. The server accepts only one
+/// pending subscription per extractor, so they are requested one after the other.
+type ResumableSubscriptions = HashMap<ExtractorIdentity, VecDeque<ResumableSubscription>>;
+
+/// An active subscription of a lost connection.
+///
+/// Keeps the sender half of the subscriber's channel, so the subscription can continue on the
+/// next connection without the subscriber noticing anything but a [`DeltasError::StreamGap`].
+#[derive(Debug)]
+struct ResumableSubscription {
+    /// The id the subscriber knows the subscription by.
+    subscription_id: Uuid,
+    extractor_id: ExtractorIdentity,
+    options: SubscriptionOptions,
+    sender: Sender<Result<BlockChanges, DeltasError>>,
+}
+
 /// Subscription State
 ///
 /// Subscription go through a lifecycle:
@@ -205,7 +310,9 @@ type WebSocketSink =
 #[derive(Debug)]
 enum SubscriptionInfo {
     /// Subscription was requested we wait for server confirmation and uuid assignment.
-    RequestedSubscription(oneshot::Sender<Result<(Uuid, Receiver<BlockChanges>), DeltasError>>),
+    RequestedSubscription(oneshot::Sender<SubscriptionResult>, SubscriptionOptions),
+    /// A subscription of a lost connection was requested again, we wait for server confirmation.
+    RequestedResubscription(ResumableSubscription),
     /// Subscription is active.
     Active,
     /// Unsubscription was requested, we wait for server confirmation.
@@ -224,23 +331,42 @@ struct Inner {
     subscriptions: HashMap<Uuid, SubscriptionInfo>,
     /// For eachs subscription we keep a sender handle, the receiver is returned to the caller of
     /// subscribe.
-    sender: HashMap<Uuid, Sender<BlockChanges>>,
+    sender: HashMap<Uuid, Sender<Result<BlockChanges, DeltasError>>>,
+    /// Extractor and options of each active subscription, to re-establish it after a reconnect.
+    active_extractors: HashMap<Uuid, (ExtractorIdentity, SubscriptionOptions)>,
+    /// Maps the server assigned ids of resumed subscriptions to the ids their subscribers know
+    /// them by. Subscriptions are always tracked by the latter.
+    resumed_ids: HashMap<Uuid, Uuid>,
+    /// Subscriptions of a previous connection that still need to be re-established.
+    resumable: ResumableSubscriptions,
     /// How many messages to buffer per subscription before starting to drop new messages.
     buffer_size: usize,
+    /// Connection lifecycle events sender.
+    events: broadcast::Sender<ConnectionEvent>,
 }
 
 /// Shared state between all client instances.
 ///
 /// This state is behind a mutex and requires synchronization to be read of modified.
 impl Inner {
-    fn new(cmd_tx: Sender<()>, sink: WebSocketSink, buffer_size: usize) -> Self {
+    fn new(
+        cmd_tx: Sender<()>,
+        sink: WebSocketSink,
+        buffer_size: usize,
+        events: broadcast::Sender<ConnectionEvent>,
+        resumable: ResumableSubscriptions,
+    ) -> Self {
         Self {
             sink,
             cmd_tx,
             pending: HashMap::new(),
             subscriptions: HashMap::new(),
             sender: HashMap::new(),
+            active_extractors: HashMap::new(),
+            resumed_ids: HashMap::new(),
+            resumable,
             buffer_size,
+            events,
         }
     }
 
@@ -249,26 +375,56 @@ impl Inner {
     fn new_subscription(
         &mut self,
         id: &ExtractorIdentity,
-        ready_tx: oneshot::Sender<Result<(Uuid, Receiver<BlockChanges>), DeltasError>>,
+        options: SubscriptionOptions,
+        ready_tx: oneshot::Sender<SubscriptionResult>,
     ) -> Result<(), DeltasError> {
         if self.pending.contains_key(id) {
             return Err(DeltasError::SubscriptionAlreadyPending);
         }
         self.pending
-            .insert(id.clone(), SubscriptionInfo::RequestedSubscription(ready_tx));
+            .insert(id.clone(), SubscriptionInfo::RequestedSubscription(ready_tx, options));
         Ok(())
     }
 
+    /// The id the subscriber knows a subscription by, given the id assigned by the server.
+    fn client_id(&self, server_id: Uuid) -> Uuid {
+        self.resumed_ids
+            .get(&server_id)
+            .copied()
+            .unwrap_or(server_id)
+    }
+
+    /// The id assigned by the server, given the id the subscriber knows a subscription by.
+    fn server_id(&self, subscription_id: Uuid) -> Uuid {
+        self.resumed_ids
+            .iter()
+            .find_map(|(server_id, id)| (*id == subscription_id).then_some(*server_id))
+            .unwrap_or(subscription_id)
+    }
+
     /// Transitions a pending subscription to active.
     ///
-    /// Will ignore any request to do so for subscriptions that are not pending.
-    fn mark_active(&mut self, extractor_id: &ExtractorIdentity, subscription_id: Uuid) {
-        if let Some(info) = self.pending.remove(extractor_id) {
-            if let SubscriptionInfo::RequestedSubscription(ready_tx) = info {
+    /// Will ignore any request to do so for subscriptions that are not pending. Returns the id the
+    /// subscriber knows the subscription by if it was resumed after a reconnect.
+    fn mark_active(
+        &mut self,
+        extractor_id: &ExtractorIdentity,
+        subscription_id: Uuid,
+    ) -> Option<Uuid> {
+        match self.pending.remove(extractor_id) {
+            Some(SubscriptionInfo::RequestedSubscription(ready_tx, options)) => {
                 let (tx, rx) = mpsc::channel(self.buffer_size);
                 self.sender.insert(subscription_id, tx);
                 self.subscriptions
                     .insert(subscription_id, SubscriptionInfo::Active);
+                self.active_extractors
+                    .insert(subscription_id, (extractor_id.clone(), options));
+                let _ = self
+                    .events
+                    .send(ConnectionEvent::SubscriptionAdded {
+                        extractor_id: extractor_id.clone(),
+                        subscription_id,
+                    });
                 let _ = ready_tx
                     .send(Ok((subscription_id, rx)))
                     .map_err(|_| {
@@ -278,26 +434,59 @@ impl Inner {
                             "Subscriber for has gone away. Ignoring."
                         )
                     });
-            } else {
+                None
+            }
+            Some(SubscriptionInfo::RequestedResubscription(resumed)) => {
+                let id = resumed.subscription_id;
+                info!(?extractor_id, subscription_id = ?id, "Resumed subscription");
+                self.resumed_ids
+                    .insert(subscription_id, id);
+                self.sender.insert(id, resumed.sender);
+                self.subscriptions
+                    .insert(id, SubscriptionInfo::Active);
+                self.active_extractors
+                    .insert(id, (resumed.extractor_id, resumed.options));
+                let _ = self
+                    .events
+                    .send(ConnectionEvent::SubscriptionResumed {
+                        extractor_id: extractor_id.clone(),
+                        subscription_id: id,
+                    });
+                Some(id)
+            }
+            Some(_) => {
                 error!(
                     ?extractor_id,
                     ?subscription_id,
                     "Pending subscription was not in the correct state to 
                     transition to active. Ignoring!"
-                )
+                );
+                None
+            }
+            None => {
+                error!(
+                    ?extractor_id,
+                    ?subscription_id,
+                    "Tried to mark an unknown subscription as active. Ignoring!"
+                );
+                None
             }
-        } else {
-            error!(
-                ?extractor_id,
-                ?subscription_id,
-                "Tried to mark an unknown subscription as active. Ignoring!"
-            );
         }
     }
 
-    /// Sends a message to a subscription's receiver.
+    /// Sends a message, or an error decoding it, to a subscription's receiver.
+    ///
+    /// Messages for subscriptions that have a pending unsubscribe request are dropped.
     #[allow(clippy::result_large_err)]
-    fn send(&mut self, id: &Uuid, msg: BlockChanges) -> Result<(), DeltasError> {
+    fn send(
+        &mut self,
+        id: &Uuid,
+        msg: Result<BlockChanges, DeltasError>,
+    ) -> Result<(), DeltasError> {
+        if let Some(SubscriptionInfo::RequestedUnsubscription(_)) = self.subscriptions.get(id) {
+            trace!(subscription_id=?id, "Subscription is ending, dropping message");
+            return Ok(())
+        }
         if let Some(sender) = self.sender.get_mut(id) {
             sender
                 .try_send(msg)
@@ -313,20 +502,23 @@ impl Inner {
 
     /// Requests a subscription to end.
     ///
-    /// The subscription needs to exist and be active for this to have any effect. Wll use
-    /// `ready_tx` to notify the receiver once the transition to ended completed.
-    fn end_subscription(&mut self, subscription_id: &Uuid, ready_tx: oneshot::Sender<()>) {
-        if let Some(info) = self
+    /// The subscription needs to be active for this to have any effect. Wll use `ready_tx` to
+    /// notify the receiver once the transition to ended completed. Returns an error if the
+    /// subscription is unknown.
+    #[allow(clippy::result_large_err)]
+    fn end_subscription(
+        &mut self,
+        subscription_id: &Uuid,
+        ready_tx: oneshot::Sender<()>,
+    ) -> Result<(), DeltasError> {
+        let info = self
             .subscriptions
             .get_mut(subscription_id)
-        {
-            if let SubscriptionInfo::Active = info {
-                *info = SubscriptionInfo::RequestedUnsubscription(ready_tx);
-            }
-        } else {
-            // no big deal imo so only debug lvl...
-            debug!(?subscription_id, "Tried unsubscribing from a non existent subscription");
+            .ok_or(DeltasError::SubscriptionNotFound(*subscription_id))?;
+        if let SubscriptionInfo::Active = info {
+            *info = SubscriptionInfo::RequestedUnsubscription(ready_tx);
         }
+        Ok(())
     }
 
     /// Removes and fully ends a subscription
@@ -354,6 +546,13 @@ impl Inner {
                     .remove(&subscription_id)
                     .ok_or_else(|| DeltasError::Fatal("sender channel missing".to_string()))?;
             }
+            self.active_extractors
+                .remove(&subscription_id);
+            self.resumed_ids
+                .retain(|_, id| *id != subscription_id);
+            let _ = self
+                .events
+                .send(ConnectionEvent::SubscriptionEnded { subscription_id });
         } else {
             // TODO: There is a race condition that can trigger multiple unsubscribes
             //  if server doesn't respond quickly enough leading to some ugly logs but
@@ -371,7 +570,7 @@ impl Inner {
     fn cancel_pending(&mut self, extractor_id: &ExtractorIdentity, error: &WebsocketError) {
         if let Some(sub_info) = self.pending.remove(extractor_id) {
             match sub_info {
-                SubscriptionInfo::RequestedSubscription(tx) => {
+                SubscriptionInfo::RequestedSubscription(tx, _) => {
                     let _ = tx
                         .send(Err(DeltasError::ServerError(
                             format!("Subscription failed: {error}"),
@@ -379,6 +578,19 @@ impl Inner {
                         )))
                         .map_err(|_| debug!("Cancel pending failed: receiver deallocated!"));
                 }
+                SubscriptionInfo::RequestedResubscription(resumed) => {
+                    let subscription_id = resumed.subscription_id;
+                    warn!(?extractor_id, ?subscription_id, %error, "Failed to resume subscription");
+                    let _ = resumed
+                        .sender
+                        .try_send(Err(DeltasError::ServerError(
+                            format!("Resubscription failed: {error}"),
+                            error.clone(),
+                        )));
+                    let _ = self
+                        .events
+                        .send(ConnectionEvent::SubscriptionEnded { subscription_id });
+                }
                 _ => {
                     error!(?extractor_id, "Pending subscription in wrong state")
                 }
@@ -388,6 +600,111 @@ impl Inner {
         }
     }
 
+    /// Requests a subscription to an extractor from the server.
+    async fn send_subscribe(
+        &mut self,
+        extractor_id: ExtractorIdentity,
+        options: &SubscriptionOptions,
+    ) -> Result<(), DeltasError> {
+        let cmd = Command::Subscribe {
+            extractor_id,
+            include_state: options.include_state,
+            compression: options.compression,
+        };
+        self.ws_send(tungstenite::protocol::Message::Text(serde_json::to_string(&cmd).map_err(
+            |e| DeltasError::TransportError(format!("Failed to serialize subscribe command: {e}")),
+        )?))
+        .await
+    }
+
+    /// Requests the next subscription of a previous connection for the given extractor, unless a
+    /// subscription to it is already pending.
+    ///
+    /// Subscriptions whose subscriber has gone away are ended instead. Failures to send are only
+    /// logged: they mean the connection is lost, which the message loop detects on its own.
+    async fn resume_next(&mut self, extractor_id: &ExtractorIdentity) {
+        if self.pending.contains_key(extractor_id) {
+            return
+        }
+        while let Some(resumed) = self
+            .resumable
+            .get_mut(extractor_id)
+            .and_then(VecDeque::pop_front)
+        {
+            let subscription_id = resumed.subscription_id;
+            if resumed.sender.is_closed() {
+                debug!(?subscription_id, "Subscriber has gone away, not resuming subscription");
+                let _ = self
+                    .events
+                    .send(ConnectionEvent::SubscriptionEnded { subscription_id });
+                continue
+            }
+            info!(?extractor_id, ?subscription_id, "Resuming subscription");
+            let options = resumed.options.clone();
+            self.pending
+                .insert(extractor_id.clone(), SubscriptionInfo::RequestedResubscription(resumed));
+            if let Err(error) = self
+                .send_subscribe(extractor_id.clone(), &options)
+                .await
+            {
+                warn!(?error, ?subscription_id, "Failed to request subscription resumption");
+            }
+            return
+        }
+        self.resumable.remove(extractor_id);
+    }
+
+    /// Requests the first subscription of a previous connection for each extractor. The others
+    /// follow once the server confirmed the previous one.
+    async fn resume_all(&mut self) {
+        let extractor_ids: Vec<_> = self.resumable.keys().cloned().collect();
+        for extractor_id in extractor_ids {
+            self.resume_next(&extractor_id).await;
+        }
+    }
+
+    /// Tears down the state of a lost connection.
+    ///
+    /// Returns the subscriptions to re-establish on the next connection, and the ids of the
+    /// subscriptions that ended with the connection.
+    fn into_resumable(mut self) -> (ResumableSubscriptions, Vec<Uuid>) {
+        let mut resumable = std::mem::take(&mut self.resumable);
+        for (extractor_id, info) in self.pending.drain() {
+            if let SubscriptionInfo::RequestedResubscription(resumed) = info {
+                resumable
+                    .entry(extractor_id)
+                    .or_default()
+                    .push_front(resumed);
+            }
+        }
+        let mut ended = Vec::new();
+        for (subscription_id, info) in self.subscriptions.drain() {
+            let sender = self.sender.remove(&subscription_id);
+            let extractor = self
+                .active_extractors
+                .remove(&subscription_id);
+            match (info, sender, extractor) {
+                (SubscriptionInfo::Active, Some(sender), Some((extractor_id, options))) => {
+                    resumable
+                        .entry(extractor_id.clone())
+                        .or_default()
+                        .push_back(ResumableSubscription {
+                            subscription_id,
+                            extractor_id,
+                            options,
+                            sender,
+                        });
+                }
+                (SubscriptionInfo::RequestedUnsubscription(ready_tx), ..) => {
+                    let _ = ready_tx.send(());
+                    ended.push(subscription_id);
+                }
+                _ => ended.push(subscription_id),
+            }
+        }
+        (resumable, ended)
+    }
+
     /// Sends a message through the websocket.
     async fn ws_send(&mut self, msg: tungstenite::protocol::Message) -> Result<(), DeltasError> {
         self.sink.send(msg).await.map_err(|e| {
@@ -407,13 +724,21 @@ impl WsDeltasClient {
         Ok(Self {
             uri,
             auth_key: auth_key.map(|s| s.to_string()),
+            server_version: TYCHO_SERVER_VERSION.to_string(),
+            ws_path: "ws".to_string(),
+            headers: Vec::new(),
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+            heartbeat_timeout: DEFAULT_HEARTBEAT_TIMEOUT,
             inner: Arc::new(Mutex::new(None)),
             ws_buffer_size: 128,
             subscription_buffer_size: 128,
             conn_notify: Arc::new(Notify::new()),
             max_reconnects: 5,
             retry_cooldown: Duration::from_millis(500),
+            max_retry_cooldown: Duration::from_millis(500),
             dead: Arc::new(AtomicBool::new(false)),
+            events: broadcast::channel(CONNECTION_EVENTS_BUFFER_SIZE).0,
+            resumable: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
@@ -432,16 +757,41 @@ impl WsDeltasClient {
         Ok(Self {
             uri,
             auth_key: auth_key.map(|s| s.to_string()),
+            server_version: TYCHO_SERVER_VERSION.to_string(),
+            ws_path: "ws".to_string(),
+            headers: Vec::new(),
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+            heartbeat_timeout: DEFAULT_HEARTBEAT_TIMEOUT,
             inner: Arc::new(Mutex::new(None)),
             ws_buffer_size: 128,
             subscription_buffer_size: 128,
             conn_notify: Arc::new(Notify::new()),
             max_reconnects,
             retry_cooldown,
+            max_retry_cooldown: retry_cooldown,
             dead: Arc::new(AtomicBool::new(false)),
+            events: broadcast::channel(CONNECTION_EVENTS_BUFFER_SIZE).0,
+            resumable: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
+    // Construct a new client with a custom number of reconnection attempts and an exponentially
+    // growing cooldown between them, starting at `retry_cooldown` and capped at
+    // `max_retry_cooldown`.
+    #[allow(clippy::result_large_err)]
+    pub fn new_with_backoff(
+        ws_uri: &str,
+        auth_key: Option<&str>,
+        max_reconnects: u64,
+        retry_cooldown: Duration,
+        max_retry_cooldown: Duration,
+    ) -> Result<Self, DeltasError> {
+        let mut client =
+            Self::new_with_reconnects(ws_uri, auth_key, max_reconnects, retry_cooldown)?;
+        client.max_retry_cooldown = max_retry_cooldown.max(retry_cooldown);
+        Ok(client)
+    }
+
     // Construct a new client with custom buffer sizes (for testing)
     #[cfg(test)]
     #[allow(clippy::result_large_err)]
@@ -457,16 +807,138 @@ impl WsDeltasClient {
         Ok(Self {
             uri,
             auth_key: auth_key.map(|s| s.to_string()),
+            server_version: TYCHO_SERVER_VERSION.to_string(),
+            ws_path: "ws".to_string(),
+            headers: Vec::new(),
+            heartbeat_interval: DEFAULT_HEARTBEAT_INTERVAL,
+            heartbeat_timeout: DEFAULT_HEARTBEAT_TIMEOUT,
             inner: Arc::new(Mutex::new(None)),
             ws_buffer_size,
             subscription_buffer_size,
             conn_notify: Arc::new(Notify::new()),
             max_reconnects: 5,
             retry_cooldown: Duration::from_millis(0),
+            max_retry_cooldown: Duration::from_millis(0),
             dead: Arc::new(AtomicBool::new(false)),
+            events: broadcast::channel(CONNECTION_EVENTS_BUFFER_SIZE).0,
+            resumable: Arc::new(Mutex::new(HashMap::new())),
         })
     }
 
+    /// Overrides the server api version used to build the websocket endpoint. Defaults to `v1`.
+    ///
+    /// Pass an empty string if the endpoint is not versioned.
+    pub fn with_server_version(mut self, version: &str) -> Self {
+        self.server_version = version.to_string();
+        self
+    }
+
+    /// Overrides the websocket endpoint path, relative to the server version. Defaults to `ws`.
+    pub fn with_ws_path(mut self, path: &str) -> Self {
+        self.ws_path = path.to_string();
+        self
+    }
+
+    /// Sets how often the client pings the server and after how long without receiving any
+    /// frame the connection is considered dead and reconnected. Defaults to 10s and 30s.
+    pub fn with_heartbeat(mut self, interval: Duration, timeout: Duration) -> Self {
+        self.heartbeat_interval = interval;
+        self.heartbeat_timeout = timeout;
+        self
+    }
+
+    /// Adds a header that is sent with the websocket handshake request, e.g. for
+    /// authenticating gateways.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Returns a receiver for the client's connection lifecycle events.
+    ///
+    /// Only events emitted after this call are received. Each receiver buffers up to 64 events; a
+    /// receiver that falls further behind skips the oldest ones and gets a `Lagged` error.
+    pub fn connection_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Drops the current connection state, if any, and reports the disconnect.
+    ///
+    /// If `resume` is set, active subscriptions are kept to be re-established on the next
+    /// connection. Otherwise they end, together with any subscription still waiting to be resumed.
+    async fn reset_connection(&self, resume: bool) {
+        let mut guard = self.inner.as_ref().lock().await;
+        let mut resumable = self.resumable.lock().await;
+        if let Some(inner) = guard.take() {
+            let _ = self
+                .events
+                .send(ConnectionEvent::Disconnected);
+            let (kept, ended) = inner.into_resumable();
+            for subscription_id in ended {
+                let _ = self
+                    .events
+                    .send(ConnectionEvent::SubscriptionEnded { subscription_id });
+            }
+            for (extractor_id, subscriptions) in kept {
+                resumable
+                    .entry(extractor_id)
+                    .or_default()
+                    .extend(subscriptions);
+            }
+        }
+        if !resume {
+            for (_, subscriptions) in resumable.drain() {
+                for subscription in subscriptions {
+                    let _ = self
+                        .events
+                        .send(ConnectionEvent::SubscriptionEnded {
+                            subscription_id: subscription.subscription_id,
+                        });
+                }
+            }
+        }
+    }
+
+    /// Full websocket endpoint uri: `{uri}/{server_version}/{ws_path}`.
+    ///
+    /// Leading and trailing slashes of the individual parts are normalised, empty parts are
+    /// skipped.
+    fn ws_uri(&self) -> String {
+        let base = self.uri.to_string();
+        [self.server_version.as_str(), self.ws_path.as_str()]
+            .into_iter()
+            .map(|part| part.trim_matches('/'))
+            .filter(|part| !part.is_empty())
+            .fold(base.trim_end_matches('/').to_string(), |uri, part| format!("{uri}/{part}"))
+    }
+
+    /// Sets the size of the client's internal websocket buffer. Defaults to 128.
+    pub fn with_ws_buffer_size(mut self, size: usize) -> Self {
+        self.ws_buffer_size = size;
+        self
+    }
+
+    /// Sets how many messages are buffered for each subscription. Defaults to 128.
+    ///
+    /// Messages are never awaited on a slow consumer: if a subscription's buffer is full, the
+    /// client unsubscribes it and its receiver is closed, so the consumer has to resubscribe.
+    pub fn with_subscription_buffer_size(mut self, size: usize) -> Self {
+        self.subscription_buffer_size = size;
+        self
+    }
+
+    /// Cooldown to wait before the given reconnection attempt.
+    ///
+    /// Doubles with each attempt, starting at `retry_cooldown` for the first retry, and is capped
+    /// at `max_retry_cooldown`.
+    fn reconnect_cooldown(&self, retry_count: u64) -> Duration {
+        let exponent = u32::try_from(retry_count.saturating_sub(1)).unwrap_or(u32::MAX);
+        self.retry_cooldown
+            .saturating_mul(2u32.saturating_pow(exponent))
+            .min(self.max_retry_cooldown)
+    }
+
     /// Ensures that the client is connected.
     ///
     /// This method will acquire the lock for inner.
@@ -477,18 +949,68 @@ impl WsDeltasClient {
 
     /// Waits for the client to be connected
     ///
-    /// This method acquires the lock for inner for a short period, then waits until the  
-    /// connection is established if not already connected.
+    /// This method acquires the lock for inner for a short period, then waits until the
+    /// connection is established if not already connected. Calls made before `connect` are
+    /// queued until the connection is live. The waiter is registered before checking the
+    /// connection state so a notification sent in between is not missed.
     async fn ensure_connection(&self) -> Result<(), DeltasError> {
         if self.dead.load(Ordering::SeqCst) {
             return Err(DeltasError::NotConnected)
         };
+        let notified = self.conn_notify.notified();
+        tokio::pin!(notified);
+        notified.as_mut().enable();
         if !self.is_connected().await {
-            self.conn_notify.notified().await;
+            notified.await;
+            // The connection task also notifies waiters once it gives up.
+            if self.dead.load(Ordering::SeqCst) {
+                return Err(DeltasError::NotConnected)
+            };
         };
         Ok(())
     }
 
+    /// Subscribes like [`DeltasClient::subscribe`], but returns the messages as a [`Stream`].
+    ///
+    /// The stream ends once the subscription ends.
+    ///
+    /// [`Stream`]: futures03::Stream
+    pub async fn subscribe_stream(
+        &self,
+        extractor_id: ExtractorIdentity,
+        options: SubscriptionOptions,
+    ) -> Result<(Uuid, BoxStream<'static, Result<BlockChanges, DeltasError>>), DeltasError> {
+        let (subscription_id, mut rx) = self
+            .subscribe(extractor_id, options)
+            .await?;
+        Ok((subscription_id, stream::poll_fn(move |cx| rx.poll_recv(cx)).boxed()))
+    }
+
+    /// Pings the server, or fails if the server has not sent any frame for too long.
+    ///
+    /// The returned error triggers the reconnect logic, which allows detecting silently dropped
+    /// (half-open) connections.
+    async fn heartbeat(&self, last_frame: Instant) -> Result<(), DeltasError> {
+        let silence = last_frame.elapsed();
+        if silence > self.heartbeat_timeout {
+            warn!(?silence, "No frame received from the server within the heartbeat timeout");
+            return Err(DeltasError::ConnectionError(Box::new(tungstenite::Error::Io(
+                std::io::Error::new(std::io::ErrorKind::TimedOut, "heartbeat timeout"),
+            ))));
+        }
+        let mut guard = self.inner.lock().await;
+        let inner = guard
+            .as_mut()
+            .ok_or_else(|| DeltasError::NotConnected)?;
+        if let Err(error) = inner
+            .ws_send(tungstenite::protocol::Message::Ping(Vec::new()))
+            .await
+        {
+            debug!(?error, "Failed to send ping!");
+        }
+        Ok(())
+    }
+
     /// Main message handling logic
     ///
     /// If the message returns an error, a reconnect attempt may be considered depending on the
@@ -505,120 +1027,181 @@ impl WsDeltasClient {
             // the serde arbitrary_precision feature (often included in many
             // dependencies we use) breaks some untagged enum deserializations. Instead,
             // we deserialize the message into a serde_json::Value and convert that into a WebSocketMessage. For more info on this issue, see: https://github.com/serde-rs/json/issues/740
-            Ok(tungstenite::protocol::Message::Text(text)) => match serde_json::from_str::<
-                serde_json::Value,
-            >(&text)
-            {
-                Ok(value) => match serde_json::from_value::<WebSocketMessage>(value) {
-                    Ok(ws_message) => match ws_message {
-                        WebSocketMessage::BlockChanges { subscription_id, deltas } => {
-                            Self::handle_block_changes_msg(&mut guard, subscription_id, deltas).await?;
-                        }
-                        WebSocketMessage::Response(Response::NewSubscription {
-                            extractor_id,
-                            subscription_id,
-                        }) => {
-                            info!(?extractor_id, ?subscription_id, "Received a new subscription");
-                            let inner = guard
-                                .as_mut()
-                                .ok_or_else(|| DeltasError::NotConnected)?;
-                            inner.mark_active(&extractor_id, subscription_id);
-                        }
-                        WebSocketMessage::Response(Response::SubscriptionEnded {
-                            subscription_id,
-                        }) => {
-                            info!(?subscription_id, "Received a subscription ended");
-                            let inner = guard
-                                .as_mut()
-                                .ok_or_else(|| DeltasError::NotConnected)?;
-                            inner.remove_subscription(subscription_id)?;
-                        }
-                        WebSocketMessage::Response(Response::Error(error)) => match &error {
-                            WebsocketError::ExtractorNotFound(extractor_id) => {
-                                let inner = guard
-                                    .as_mut()
-                                    .ok_or_else(|| DeltasError::NotConnected)?;
-                                inner.cancel_pending(extractor_id, &error);
-                            }
-                            WebsocketError::SubscriptionNotFound(subscription_id) => {
-                                debug!("Received subscription not found, removing subscription");
-                                let inner = guard
-                                    .as_mut()
-                                    .ok_or_else(|| DeltasError::NotConnected)?;
-                                inner.remove_subscription(*subscription_id)?;
-                            }
-                            WebsocketError::ParseError(raw, e) => {
-                                return Err(DeltasError::ServerError(
-                                    format!(
-                                        "Server failed to parse client message: {e}, msg: {raw}"
-                                    ),
-                                    error.clone(),
-                                ))
-                            }
-                            WebsocketError::CompressionError(subscription_id, e) => {
-                                return Err(DeltasError::ServerError(
-                                    format!(
-                                        "Server failed to compress message for subscription: {subscription_id}, error: {e}"
-                                    ),
-                                    error.clone(),
-                                ))
-                            }
-                            WebsocketError::SubscribeError(extractor_id) => {
-                                let inner = guard
-                                    .as_mut()
-                                    .ok_or_else(|| DeltasError::NotConnected)?;
-                                inner.cancel_pending(extractor_id, &error);
+            Ok(tungstenite::protocol::Message::Text(text)) => {
+                match serde_json::from_str::<serde_json::Value>(&text) {
+                    Ok(value) => {
+                        let subscription_id = Self::subscription_id_of(&value);
+                        match serde_json::from_value::<WebSocketMessage>(value) {
+                            Ok(ws_message) => match ws_message {
+                                WebSocketMessage::BlockChanges {
+                                    subscription_id,
+                                    deltas,
+                                } => {
+                                    Self::handle_block_changes_msg(
+                                        &mut guard,
+                                        subscription_id,
+                                        deltas,
+                                    )
+                                    .await?;
+                                }
+                                WebSocketMessage::Response(Response::NewSubscription {
+                                    extractor_id,
+                                    subscription_id,
+                                }) => {
+                                    info!(
+                                        ?extractor_id,
+                                        ?subscription_id,
+                                        "Received a new subscription"
+                                    );
+                                    let inner = guard
+                                        .as_mut()
+                                        .ok_or_else(|| DeltasError::NotConnected)?;
+                                    if let Some(subscription_id) =
+                                        inner.mark_active(&extractor_id, subscription_id)
+                                    {
+                                        Self::send_to_subscriber(
+                                            inner,
+                                            subscription_id,
+                                            Err(DeltasError::StreamGap),
+                                        )
+                                        .await;
+                                    }
+                                    inner.resume_next(&extractor_id).await;
+                                }
+                                WebSocketMessage::Response(Response::SubscriptionEnded {
+                                    subscription_id,
+                                }) => {
+                                    info!(?subscription_id, "Received a subscription ended");
+                                    let inner = guard
+                                        .as_mut()
+                                        .ok_or_else(|| DeltasError::NotConnected)?;
+                                    inner.remove_subscription(inner.client_id(subscription_id))?;
+                                }
+                                WebSocketMessage::Response(Response::Error(error)) => match &error {
+                                    WebsocketError::ExtractorNotFound(extractor_id) => {
+                                        let inner = guard
+                                            .as_mut()
+                                            .ok_or_else(|| DeltasError::NotConnected)?;
+                                        inner.cancel_pending(extractor_id, &error);
+                                        inner.resume_next(extractor_id).await;
+                                    }
+                                    WebsocketError::SubscriptionNotFound(subscription_id) => {
+                                        debug!(
+                                            "Received subscription not found, removing subscription"
+                                        );
+                                        let inner = guard
+                                            .as_mut()
+                                            .ok_or_else(|| DeltasError::NotConnected)?;
+                                        let subscription_id = inner.client_id(*subscription_id);
+                                        inner.remove_subscription(subscription_id)?;
+                                    }
+                                    WebsocketError::ParseError(raw, e) => {
+                                        return Err(DeltasError::ServerError(
+                                            format!(
+                                                "Server failed to parse client message: {e}, msg: {raw}"
+                                            ),
+                                            error.clone(),
+                                        ))
+                                    }
+                                    WebsocketError::CompressionError(subscription_id, e) => {
+                                        return Err(DeltasError::ServerError(
+                                            format!(
+                                                "Server failed to compress message for subscription: {subscription_id}, error: {e}"
+                                            ),
+                                            error.clone(),
+                                        ))
+                                    }
+                                    WebsocketError::SubscribeError(extractor_id) => {
+                                        let inner = guard
+                                            .as_mut()
+                                            .ok_or_else(|| DeltasError::NotConnected)?;
+                                        inner.cancel_pending(extractor_id, &error);
+                                        inner.resume_next(extractor_id).await;
+                                    }
+                                },
+                            },
+                            Err(e) => {
+                                let message = truncate_for_log(&text).into_owned();
+                                error!(
+                                    "Failed to deserialize WebSocketMessage: {}. \nMessage: {}",
+                                    e, message
+                                );
+                                Self::handle_undecodable_msg(
+                                    &mut guard,
+                                    subscription_id,
+                                    e.to_string(),
+                                    message,
+                                )
+                                .await;
                             }
-                        },
-                    },
+                        }
+                    }
                     Err(e) => {
                         error!(
-                            "Failed to deserialize WebSocketMessage: {}. \nMessage: {}",
-                            e, text
+                            "Failed to deserialize message: invalid JSON. {} \nMessage: {}",
+                            e,
+                            truncate_for_log(&text)
                         );
                     }
-                },
-                Err(e) => {
-                    error!(
-                        "Failed to deserialize message: invalid JSON. {} \nMessage: {}",
-                        e, text
-                    );
                 }
-            },
+            }
             Ok(tungstenite::protocol::Message::Binary(data)) => {
                 // Decompress the zstd-compressed data,
                 // Note that we only support compressed BlockChanges messages for now.
                 match zstd::decode_all(data.as_slice()) {
-                    Ok(decompressed) => match serde_json::from_slice::<serde_json::Value>(decompressed.as_slice()) {
-                                Ok(value) => match serde_json::from_value::<WebSocketMessage>(value.clone()) {
+                    Ok(decompressed) => {
+                        match serde_json::from_slice::<serde_json::Value>(decompressed.as_slice()) {
+                            Ok(value) => {
+                                let subscription_id = Self::subscription_id_of(&value);
+                                match serde_json::from_value::<WebSocketMessage>(value) {
                                     Ok(ws_message) => match ws_message {
-                                        WebSocketMessage::BlockChanges { subscription_id, deltas } => {
-                                            Self::handle_block_changes_msg(&mut guard, subscription_id, deltas).await?;
+                                        WebSocketMessage::BlockChanges {
+                                            subscription_id,
+                                            deltas,
+                                        } => {
+                                            Self::handle_block_changes_msg(
+                                                &mut guard,
+                                                subscription_id,
+                                                deltas,
+                                            )
+                                            .await?;
                                         }
                                         _ => {
                                             error!(
                                                 "Received unsupported compressed WebSocketMessage variant. \nMessage: {ws_message:?}",
                                             );
                                         }
-
                                     },
                                     Err(e) => {
+                                        let message = truncate_for_log(&String::from_utf8_lossy(
+                                            &decompressed,
+                                        ))
+                                        .into_owned();
                                         error!(
-                                            "Failed to deserialize compressed WebSocketMessage: {e}. \nMessage: {value:?}",
+                                            "Failed to deserialize compressed WebSocketMessage: {e}. \nMessage: {message}",
                                         );
+                                        Self::handle_undecodable_msg(
+                                            &mut guard,
+                                            subscription_id,
+                                            e.to_string(),
+                                            message,
+                                        )
+                                        .await;
                                     }
-                                },
-                                Err(e) => {
-                                    error!(
-                                        "Failed to deserialize compressed message: invalid JSON. {e}",
-                                    );
                                 }
-                            },
+                            }
+                            Err(e) => {
+                                error!(
+                                    "Failed to deserialize compressed message: invalid JSON. {e}",
+                                );
+                            }
+                        }
+                    }
                     Err(e) => {
                         error!("Failed to decompress zstd data: {}", e);
                     }
                 }
-            },
+            }
             Ok(tungstenite::protocol::Message::Ping(_)) => {
                 // Respond to pings with pongs.
                 let inner = guard
@@ -667,7 +1250,20 @@ impl WsDeltasClient {
         let inner = guard
             .as_mut()
             .ok_or_else(|| DeltasError::NotConnected)?;
-        match inner.send(&subscription_id, deltas) {
+        let subscription_id = inner.client_id(subscription_id);
+        Self::send_to_subscriber(inner, subscription_id, Ok(deltas)).await;
+        Ok(())
+    }
+
+    /// Forwards a message, or an error decoding it, to the subscriber.
+    ///
+    /// Ends the subscription if the subscriber can't keep up or has gone away.
+    async fn send_to_subscriber(
+        inner: &mut Inner,
+        subscription_id: Uuid,
+        msg: Result<BlockChanges, DeltasError>,
+    ) {
+        match inner.send(&subscription_id, msg) {
             Err(DeltasError::BufferFull) => {
                 error!(?subscription_id, "Buffer full, unsubscribing!");
                 Self::force_unsubscribe(subscription_id, inner).await;
@@ -678,7 +1274,41 @@ impl WsDeltasClient {
             }
             _ => { /* Do nothing */ }
         }
-        Ok(())
+    }
+
+    /// Reports a message that failed to deserialize to the subscription it belongs to.
+    ///
+    /// Silently skipping the message would leave a gap in the subscriber's stream, so the
+    /// subscriber receives a [`DeltasError::DecodeError`] instead. Messages that can't be
+    /// attributed to a known subscription are only logged.
+    async fn handle_undecodable_msg(
+        guard: &mut MutexGuard<'_, Option<Inner>>,
+        subscription_id: Option<Uuid>,
+        error: String,
+        message: String,
+    ) {
+        let Some(inner) = guard.as_mut() else { return };
+        let Some(subscription_id) = subscription_id.map(|id| inner.client_id(id)) else { return };
+        if inner
+            .subscriptions
+            .contains_key(&subscription_id)
+        {
+            warn!(?subscription_id, "Received undecodable message, notifying subscriber");
+            Self::send_to_subscriber(
+                inner,
+                subscription_id,
+                Err(DeltasError::DecodeError { error, message }),
+            )
+            .await;
+        }
+    }
+
+    /// Reads the subscription id from a message, without requiring the rest of it to be valid.
+    fn subscription_id_of(value: &serde_json::Value) -> Option<Uuid> {
+        value
+            .get("subscription_id")
+            .and_then(|id| id.as_str())
+            .and_then(|id| Uuid::parse_str(id).ok())
     }
 
     /// Forcefully ends a (client) stream by unsubscribing.
@@ -721,8 +1351,8 @@ impl WsDeltasClient {
         ready_tx: oneshot::Sender<()>,
     ) -> Result<(), DeltasError> {
         debug!(?subscription_id, "Unsubscribing");
-        inner.end_subscription(&subscription_id, ready_tx);
-        let cmd = Command::Unsubscribe { subscription_id };
+        inner.end_subscription(&subscription_id, ready_tx)?;
+        let cmd = Command::Unsubscribe { subscription_id: inner.server_id(subscription_id) };
         inner
             .ws_send(tungstenite::protocol::Message::Text(serde_json::to_string(&cmd).map_err(
                 |e| {
@@ -743,7 +1373,7 @@ impl DeltasClient for WsDeltasClient {
         &self,
         extractor_id: ExtractorIdentity,
         options: SubscriptionOptions,
-    ) -> Result<(Uuid, Receiver<BlockChanges>), DeltasError> {
+    ) -> Result<(Uuid, Receiver<Result<BlockChanges, DeltasError>>), DeltasError> {
         trace!("Starting subscribe");
         self.ensure_connection().await?;
         let (ready_tx, ready_rx) = oneshot::channel();
@@ -753,20 +1383,9 @@ impl DeltasClient for WsDeltasClient {
                 .as_mut()
                 .ok_or_else(|| DeltasError::NotConnected)?;
             trace!("Sending subscribe command");
-            inner.new_subscription(&extractor_id, ready_tx)?;
-            let cmd = Command::Subscribe {
-                extractor_id,
-                include_state: options.include_state,
-                compression: options.compression,
-            };
+            inner.new_subscription(&extractor_id, options.clone(), ready_tx)?;
             inner
-                .ws_send(tungstenite::protocol::Message::Text(
-                    serde_json::to_string(&cmd).map_err(|e| {
-                        DeltasError::TransportError(format!(
-                            "Failed to serialize subscribe command: {e}"
-                        ))
-                    })?,
-                ))
+                .send_subscribe(extractor_id, &options)
                 .await?;
         }
         trace!("Waiting for subscription response");
@@ -801,23 +1420,44 @@ impl DeltasClient for WsDeltasClient {
         if self.is_connected().await {
             return Err(DeltasError::AlreadyConnected);
         }
-        let ws_uri = format!("{uri}{TYCHO_SERVER_VERSION}/ws", uri = self.uri);
+        let ws_uri = self.ws_uri();
         info!(?ws_uri, "Starting TychoWebsocketClient");
+        let host = self
+            .uri
+            .host()
+            .ok_or_else(|| {
+                DeltasError::UriParsing(ws_uri.clone(), "No host found in tycho url".to_string())
+            })?
+            .to_string();
 
         let (cmd_tx, mut cmd_rx) = mpsc::channel(self.ws_buffer_size);
         {
             let mut guard = self.inner.as_ref().lock().await;
             *guard = None;
         }
+        // Reports the outcome of the initial connection attempts back to this method.
+        let (conn_tx, conn_rx) = oneshot::channel();
+        let mut conn_tx = Some(conn_tx);
         let this = self.clone();
         let jh = tokio::spawn(async move {
             let mut retry_count = 0;
             let mut result = Err(DeltasError::NotConnected);
+            let mut last_error = None;
 
             'retry: while retry_count < this.max_reconnects {
                 info!(?ws_uri, retry_count, "Connecting to WebSocket server");
                 if retry_count > 0 {
-                    sleep(this.retry_cooldown).await;
+                    let cooldown = this.reconnect_cooldown(retry_count);
+                    warn!(
+                        retry_count,
+                        max_reconnects = this.max_reconnects,
+                        ?cooldown,
+                        "Waiting before reconnect attempt"
+                    );
+                    let _ = this
+                        .events
+                        .send(ConnectionEvent::Reconnecting { attempt: retry_count });
+                    sleep(cooldown).await;
                 }
 
                 // Create a WebSocket request
@@ -827,15 +1467,7 @@ impl DeltasClient for WsDeltasClient {
                     .header(SEC_WEBSOCKET_VERSION, 13)
                     .header(CONNECTION, "Upgrade")
                     .header(UPGRADE, "websocket")
-                    .header(
-                        HOST,
-                        this.uri.host().ok_or_else(|| {
-                            DeltasError::UriParsing(
-                                ws_uri.clone(),
-                                "No host found in tycho url".to_string(),
-                            )
-                        })?,
-                    )
+                    .header(HOST, &host)
                     .header(
                         USER_AGENT,
                         format!("tycho-client-{version}", version = env!("CARGO_PKG_VERSION")),
@@ -845,22 +1477,31 @@ impl DeltasClient for WsDeltasClient {
                 if let Some(ref key) = this.auth_key {
                     request_builder = request_builder.header(AUTHORIZATION, key);
                 }
+                for (name, value) in &this.headers {
+                    request_builder = request_builder.header(name, value);
+                }
 
-                let request = request_builder.body(()).map_err(|e| {
-                    DeltasError::TransportError(format!("Failed to build connection request: {e}"))
-                })?;
+                let request = match request_builder.body(()) {
+                    Ok(request) => request,
+                    Err(e) => {
+                        result = Err(DeltasError::TransportError(format!(
+                            "Failed to build connection request: {e}"
+                        )));
+                        break 'retry;
+                    }
+                };
                 let (conn, _) = match connect_async(request).await {
                     Ok(conn) => conn,
                     Err(e) => {
                         // Prepare for reconnection
                         retry_count += 1;
-                        let mut guard = this.inner.as_ref().lock().await;
-                        *guard = None;
+                        this.reset_connection(true).await;
 
                         warn!(
                             e = e.to_string(),
                             "Failed to connect to WebSocket server; Reconnecting"
                         );
+                        last_error = Some(e);
                         continue 'retry;
                     }
                 };
@@ -868,19 +1509,42 @@ impl DeltasClient for WsDeltasClient {
                 let (ws_tx_new, ws_rx_new) = conn.split();
                 {
                     let mut guard = this.inner.as_ref().lock().await;
-                    *guard =
-                        Some(Inner::new(cmd_tx.clone(), ws_tx_new, this.subscription_buffer_size));
+                    let resumable = std::mem::take(&mut *this.resumable.lock().await);
+                    guard
+                        .insert(Inner::new(
+                            cmd_tx.clone(),
+                            ws_tx_new,
+                            this.subscription_buffer_size,
+                            this.events.clone(),
+                            resumable,
+                        ))
+                        .resume_all()
+                        .await;
                 }
                 let mut msg_rx = ws_rx_new.boxed();
+                let mut heartbeat =
+                    interval_at(Instant::now() + this.heartbeat_interval, this.heartbeat_interval);
+                heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+                let mut last_frame = Instant::now();
 
                 info!("Connection Successful: TychoWebsocketClient started");
+                let _ = this
+                    .events
+                    .send(ConnectionEvent::Connected);
                 this.conn_notify.notify_waiters();
+                if let Some(tx) = conn_tx.take() {
+                    let _ = tx.send(Ok(()));
+                }
+                last_error = None;
                 result = Ok(());
 
                 loop {
                     let res = tokio::select! {
                         msg = msg_rx.next() => match msg {
-                            Some(msg) => this.handle_msg(msg).await,
+                            Some(msg) => {
+                                last_frame = Instant::now();
+                                this.handle_msg(msg).await
+                            }
                             None => {
                                 // This code should not be reachable since the stream
                                 // should return ConnectionClosed in the case above
@@ -890,6 +1554,7 @@ impl DeltasClient for WsDeltasClient {
                             }
                         },
                         _ = cmd_rx.recv() => {break 'retry},
+                        _ = heartbeat.tick() => this.heartbeat(last_frame).await,
                     };
                     if let Err(error) = res {
                         debug!(?error, "WsError");
@@ -899,8 +1564,7 @@ impl DeltasClient for WsDeltasClient {
                         ) {
                             // Prepare for reconnection
                             retry_count += 1;
-                            let mut guard = this.inner.as_ref().lock().await;
-                            *guard = None;
+                            this.reset_connection(true).await;
 
                             warn!(
                                 ?error,
@@ -923,26 +1587,31 @@ impl DeltasClient for WsDeltasClient {
                 "Reconnection loop ended"
             );
             // Clean up before exiting
-            let mut guard = this.inner.as_ref().lock().await;
-            *guard = None;
+            this.reset_connection(false).await;
 
             // Check if max retries has been reached.
             if retry_count >= this.max_reconnects {
                 error!("Max reconnection attempts reached; Exiting");
                 this.dead.store(true, Ordering::SeqCst);
-                this.conn_notify.notify_waiters(); // Notify that the task is done
-                result = Err(DeltasError::ConnectionClosed);
+                result = Err(last_error
+                    .map(|e| DeltasError::ConnectionError(Box::new(e)))
+                    .unwrap_or(DeltasError::ConnectionClosed));
+            }
+            this.conn_notify.notify_waiters(); // Notify that the task is done
+
+            // If we never connected, hand the error to the caller of `connect`.
+            if let Some(tx) = conn_tx.take() {
+                let _ = tx.send(result);
+                return Err(DeltasError::NotConnected)
             }
 
             result
         });
 
-        self.conn_notify.notified().await;
-
-        if self.is_connected().await {
-            Ok(jh)
-        } else {
-            Err(DeltasError::NotConnected)
+        match conn_rx.await {
+            Ok(Ok(())) => Ok(jh),
+            Ok(Err(e)) => Err(e),
+            Err(_) => Err(DeltasError::Fatal("Connection task ended unexpectedly".to_string())),
         }
     }
 
@@ -981,6 +1650,13 @@ mod tests {
     async fn mock_tycho_ws(
         messages: &[ExpectedComm],
         reconnects: usize,
+    ) -> (SocketAddr, JoinHandle<()>) {
+        mock_tycho_ws_sessions(vec![messages.to_vec(); reconnects + 1]).await
+    }
+
+    /// Like `mock_tycho_ws`, but follows a different script on each connection.
+    async fn mock_tycho_ws_sessions(
+        sessions: Vec<Vec<ExpectedComm>>,
     ) -> (SocketAddr, JoinHandle<()>) {
         info!("Starting mock webserver");
         // zero port here means the OS chooses an open port
@@ -988,11 +1664,10 @@ mod tests {
             .await
             .expect("localhost bind failed");
         let addr = server.local_addr().unwrap();
-        let messages = messages.to_vec();
 
         let jh = tokio::spawn(async move {
             info!("mock webserver started");
-            for _ in 0..(reconnects + 1) {
+            for messages in sessions {
                 info!("Awaiting client connections");
                 if let Ok((stream, _)) = server.accept().await {
                     info!("Client connected");
@@ -1001,7 +1676,7 @@ mod tests {
                         .unwrap();
 
                     info!("Handling messages..");
-                    for c in messages.iter().cloned() {
+                    for c in messages {
                         match c {
                             ExpectedComm::Receive(t, exp) => {
                                 info!("Awaiting message...");
@@ -1202,30 +1877,235 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_compressed_subscribe_receive() {
-        let compressed_block_deltas = zstd::encode_all(
-            BLOCK_DELTAS.as_bytes(),
-            0, // default compression level
-        )
-        .expect("Failed to compress block deltas message");
-
+    async fn test_subscribe_stream() {
         let exp_comm = [
             ExpectedComm::Receive(
                 100,
                 tungstenite::protocol::Message::Text(
-                    r#"
-                {
-                    "method":"subscribe",
-                    "extractor_id":{
-                        "chain":"ethereum",
-                        "name":"vm:ambient"
-                    },
-                    "include_state": true,
-                    "compression": true
-                }"#
-                    .to_owned()
-                    .replace(|c: char| c.is_whitespace(), ""),
-                ),
+                    SUBSCRIBE
+                        .to_owned()
+                        .replace(|c: char| c.is_whitespace(), ""),
+                ),
+            ),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(
+                SUBSCRIPTION_CONFIRMATION
+                    .to_owned()
+                    .replace(|c: char| c.is_whitespace(), ""),
+            )),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(BLOCK_DELTAS.to_owned())),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(
+                SUBSCRIPTION_ENDED
+                    .to_owned()
+                    .replace(|c: char| c.is_whitespace(), ""),
+            )),
+        ];
+        let (addr, server_thread) = mock_tycho_ws(&exp_comm, 0).await;
+
+        let client = WsDeltasClient::new(&format!("ws://{addr}"), None).unwrap();
+        let jh = client
+            .connect()
+            .await
+            .expect("connect failed");
+        let (_, stream) = timeout(
+            Duration::from_millis(100),
+            client.subscribe_stream(
+                ExtractorIdentity::new(Chain::Ethereum, "vm:ambient"),
+                SubscriptionOptions::new().with_compression(false),
+            ),
+        )
+        .await
+        .expect("subscription timed out")
+        .expect("subscription failed");
+
+        let block_numbers = timeout(
+            Duration::from_millis(100),
+            stream
+                .map(|msg| msg.expect("decode failed").block.number)
+                .collect::<Vec<_>>(),
+        )
+        .await
+        .expect("stream did not end");
+
+        assert_eq!(block_numbers, vec![123]);
+
+        timeout(Duration::from_millis(100), client.close())
+            .await
+            .expect("close timed out")
+            .expect("close failed");
+        jh.await
+            .expect("ws loop errored")
+            .unwrap();
+        server_thread.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_subscriptions_through_shared_client() {
+        let server = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("localhost bind failed");
+        let addr = server.local_addr().unwrap();
+        let server_thread = tokio::spawn(async move {
+            let (stream, _) = server.accept().await.unwrap();
+            let mut websocket = tokio_tungstenite::accept_async(stream)
+                .await
+                .unwrap();
+            // Subscriptions may arrive in any order, so confirm each one for the extractor it
+            // requested, then end each subscription as soon as it asks to unsubscribe.
+            let mut subscription_ids = HashMap::new();
+            while let Some(Ok(msg)) = websocket.next().await {
+                let tungstenite::protocol::Message::Text(text) = msg else { continue };
+                let request: serde_json::Value = serde_json::from_str(&text).unwrap();
+                let response = match request["method"].as_str() {
+                    Some("subscribe") => {
+                        let extractor_id = request["extractor_id"].clone();
+                        let subscription_id = Uuid::new_v4();
+                        subscription_ids.insert(subscription_id, extractor_id.clone());
+                        serde_json::json!({
+                            "method": "newsubscription",
+                            "extractor_id": extractor_id,
+                            "subscription_id": subscription_id,
+                        })
+                    }
+                    Some("unsubscribe") => serde_json::json!({
+                        "method": "subscriptionended",
+                        "subscription_id": request["subscription_id"],
+                    }),
+                    _ => panic!("unexpected request: {text}"),
+                };
+                websocket
+                    .send(tungstenite::protocol::Message::Text(response.to_string()))
+                    .await
+                    .expect("Failed to send message");
+            }
+            subscription_ids
+        });
+
+        let client: Arc<dyn DeltasClient + Send + Sync> =
+            Arc::new(WsDeltasClient::new(&format!("ws://{addr}"), None).unwrap());
+        let jh = client
+            .connect()
+            .await
+            .expect("connect failed");
+        let tasks = ["vm:ambient", "uniswap_v2"].map(|name| {
+            let client = Arc::clone(&client);
+            tokio::spawn(async move {
+                let (subscription_id, _rx) = timeout(
+                    Duration::from_millis(500),
+                    client.subscribe(
+                        ExtractorIdentity::new(Chain::Ethereum, name),
+                        SubscriptionOptions::new().with_compression(false),
+                    ),
+                )
+                .await
+                .expect("subscription timed out")
+                .expect("subscription failed");
+                timeout(Duration::from_millis(500), client.unsubscribe(subscription_id))
+                    .await
+                    .expect("unsubscribe timed out")
+                    .expect("unsubscribe failed");
+                subscription_id
+            })
+        });
+        let mut subscription_ids = Vec::new();
+        for task in tasks {
+            subscription_ids.push(task.await.expect("task panicked"));
+        }
+        timeout(Duration::from_millis(100), client.close())
+            .await
+            .expect("close timed out")
+            .expect("close failed");
+        jh.await
+            .expect("ws loop errored")
+            .unwrap();
+        let confirmed = server_thread.await.unwrap();
+
+        assert_ne!(subscription_ids[0], subscription_ids[1]);
+        assert_eq!(confirmed[&subscription_ids[0]]["name"], "vm:ambient");
+        assert_eq!(confirmed[&subscription_ids[1]]["name"], "uniswap_v2");
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_before_connect_is_queued() {
+        let exp_comm = [
+            ExpectedComm::Receive(
+                500,
+                tungstenite::protocol::Message::Text(
+                    SUBSCRIBE
+                        .to_owned()
+                        .replace(|c: char| c.is_whitespace(), ""),
+                ),
+            ),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(
+                SUBSCRIPTION_CONFIRMATION
+                    .to_owned()
+                    .replace(|c: char| c.is_whitespace(), ""),
+            )),
+        ];
+        let (addr, server_thread) = mock_tycho_ws(&exp_comm, 0).await;
+
+        let client = WsDeltasClient::new(&format!("ws://{addr}"), None).unwrap();
+        let subscriber = client.clone();
+        let subscription = tokio::spawn(async move {
+            subscriber
+                .subscribe(
+                    ExtractorIdentity::new(Chain::Ethereum, "vm:ambient"),
+                    SubscriptionOptions::new().with_compression(false),
+                )
+                .await
+        });
+        // give the subscribe call time to start waiting for the connection
+        sleep(Duration::from_millis(50)).await;
+        assert!(!subscription.is_finished());
+
+        let jh = client
+            .connect()
+            .await
+            .expect("connect failed");
+        let (subscription_id, _rx) = timeout(Duration::from_millis(500), subscription)
+            .await
+            .expect("subscription timed out")
+            .expect("subscription task panicked")
+            .expect("subscription failed");
+        assert_eq!(
+            subscription_id,
+            Uuid::parse_str("30b740d1-cf09-4e0e-8cfe-b1434d447ece").unwrap()
+        );
+
+        timeout(Duration::from_millis(100), client.close())
+            .await
+            .expect("close timed out")
+            .expect("close failed");
+        jh.await
+            .expect("ws loop errored")
+            .unwrap();
+        server_thread.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_compressed_subscribe_receive() {
+        let compressed_block_deltas = zstd::encode_all(
+            BLOCK_DELTAS.as_bytes(),
+            0, // default compression level
+        )
+        .expect("Failed to compress block deltas message");
+
+        let exp_comm = [
+            ExpectedComm::Receive(
+                100,
+                tungstenite::protocol::Message::Text(
+                    r#"
+                {
+                    "method":"subscribe",
+                    "extractor_id":{
+                        "chain":"ethereum",
+                        "name":"vm:ambient"
+                    },
+                    "include_state": true,
+                    "compression": true
+                }"#
+                    .to_owned()
+                    .replace(|c: char| c.is_whitespace(), ""),
+                ),
             ),
             ExpectedComm::Send(tungstenite::protocol::Message::Text(
                 SUBSCRIPTION_CONFIRMATION
@@ -1334,6 +2214,104 @@ mod tests {
         server_thread.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_unsubscribe_unknown_subscription() {
+        let (addr, server_thread) = mock_tycho_ws(&[], 0).await;
+
+        let client = WsDeltasClient::new(&format!("ws://{addr}"), None).unwrap();
+        let jh = client
+            .connect()
+            .await
+            .expect("connect failed");
+        let unknown_id = Uuid::new_v4();
+
+        let res = timeout(Duration::from_millis(100), client.unsubscribe(unknown_id))
+            .await
+            .expect("unsubscribe timed out");
+
+        assert!(matches!(res, Err(DeltasError::SubscriptionNotFound(id)) if id == unknown_id));
+
+        timeout(Duration::from_millis(100), client.close())
+            .await
+            .expect("close timed out")
+            .expect("close failed");
+        jh.await
+            .expect("ws loop errored")
+            .unwrap();
+        server_thread.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_no_messages_after_unsubscribe() {
+        let exp_comm = [
+            ExpectedComm::Receive(
+                100,
+                tungstenite::protocol::Message::Text(
+                    SUBSCRIBE
+                        .to_owned()
+                        .replace(|c: char| c.is_whitespace(), ""),
+                ),
+            ),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(
+                SUBSCRIPTION_CONFIRMATION
+                    .to_owned()
+                    .replace(|c: char| c.is_whitespace(), ""),
+            )),
+            ExpectedComm::Receive(
+                100,
+                tungstenite::protocol::Message::Text(
+                    UNSUBSCRIBE
+                        .to_owned()
+                        .replace(|c: char| c.is_whitespace(), ""),
+                ),
+            ),
+            // The server may still emit deltas before it processed the unsubscribe.
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(BLOCK_DELTAS.to_owned())),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(
+                SUBSCRIPTION_ENDED
+                    .to_owned()
+                    .replace(|c: char| c.is_whitespace(), ""),
+            )),
+        ];
+        let (addr, server_thread) = mock_tycho_ws(&exp_comm, 0).await;
+
+        let client = WsDeltasClient::new(&format!("ws://{addr}"), None).unwrap();
+        let jh = client
+            .connect()
+            .await
+            .expect("connect failed");
+        let (sub_id, mut rx) = timeout(
+            Duration::from_millis(100),
+            client.subscribe(
+                ExtractorIdentity::new(Chain::Ethereum, "vm:ambient"),
+                SubscriptionOptions::new().with_compression(false),
+            ),
+        )
+        .await
+        .expect("subscription timed out")
+        .expect("subscription failed");
+
+        timeout(Duration::from_millis(100), client.unsubscribe(sub_id))
+            .await
+            .expect("unsubscribe timed out")
+            .expect("unsubscribe failed");
+        let res = timeout(Duration::from_millis(100), rx.recv())
+            .await
+            .expect("awaiting message timeout out");
+
+        // The late deltas message must not be delivered.
+        assert!(res.is_none());
+
+        timeout(Duration::from_millis(100), client.close())
+            .await
+            .expect("close timed out")
+            .expect("close failed");
+        jh.await
+            .expect("ws loop errored")
+            .unwrap();
+        server_thread.await.unwrap();
+    }
+
     #[tokio::test]
     async fn test_subscription_unexpected_end() {
         let exp_comm = [
@@ -1390,6 +2368,83 @@ mod tests {
         server_thread.await.unwrap();
     }
 
+    #[tokio::test]
+    async fn test_undecodable_message_is_reported() {
+        let undecodable =
+            r#"{"subscription_id": "30b740d1-cf09-4e0e-8cfe-b1434d447ece", "deltas": {}}"#;
+        let exp_comm = [
+            ExpectedComm::Receive(
+                100,
+                tungstenite::protocol::Message::Text(
+                    SUBSCRIBE
+                        .to_owned()
+                        .replace(|c: char| c.is_whitespace(), ""),
+                ),
+            ),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(
+                SUBSCRIPTION_CONFIRMATION
+                    .to_owned()
+                    .replace(|c: char| c.is_whitespace(), ""),
+            )),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(undecodable.to_owned())),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(BLOCK_DELTAS.to_owned())),
+        ];
+        let (addr, server_thread) = mock_tycho_ws(&exp_comm, 0).await;
+
+        let client = WsDeltasClient::new(&format!("ws://{addr}"), None).unwrap();
+        let jh = client
+            .connect()
+            .await
+            .expect("connect failed");
+        let (_, mut rx) = timeout(
+            Duration::from_millis(100),
+            client.subscribe(
+                ExtractorIdentity::new(Chain::Ethereum, "vm:ambient"),
+                SubscriptionOptions::new().with_compression(false),
+            ),
+        )
+        .await
+        .expect("subscription timed out")
+        .expect("subscription failed");
+
+        let res = timeout(Duration::from_millis(100), rx.recv())
+            .await
+            .expect("awaiting message timeout out")
+            .expect("receiving message failed");
+        match res {
+            Err(DeltasError::DecodeError { message, .. }) => assert_eq!(message, undecodable),
+            other => panic!("Expected a decode error, got {other:?}"),
+        }
+
+        // The subscription stays active, later messages are still delivered.
+        let _ = timeout(Duration::from_millis(100), rx.recv())
+            .await
+            .expect("awaiting message timeout out")
+            .expect("receiving message failed")
+            .expect("decoding message failed");
+        timeout(Duration::from_millis(100), client.close())
+            .await
+            .expect("close timed out")
+            .expect("close failed");
+        jh.await
+            .expect("ws loop errored")
+            .unwrap();
+        server_thread.await.unwrap();
+    }
+
+    #[test]
+    fn test_truncate_for_log() {
+        let short = "a".repeat(MAX_LOGGED_MESSAGE_LEN);
+        assert_eq!(truncate_for_log(&short), short);
+
+        // multi byte chars must not be split
+        let long = "é".repeat(MAX_LOGGED_MESSAGE_LEN);
+        let truncated = truncate_for_log(&long);
+        assert!(truncated.starts_with(&"é".repeat(MAX_LOGGED_MESSAGE_LEN / 2)));
+        assert!(truncated
+            .ends_with(&format!("... ({} bytes truncated)", long.len() - MAX_LOGGED_MESSAGE_LEN)));
+    }
+
     #[test_log::test(tokio::test)]
     async fn test_reconnect() {
         let exp_comm = [
@@ -1496,30 +2551,40 @@ mod tests {
             .await
             .expect("connect failed");
 
-        for _ in 0..2 {
-            dbg!("loop");
-            let (_, mut rx) = timeout(
-                Duration::from_millis(200),
-                client.subscribe(
-                    ExtractorIdentity::new(Chain::Ethereum, "vm:ambient"),
-                    SubscriptionOptions::new().with_compression(false),
-                ),
-            )
+        let (_, mut rx) = timeout(
+            Duration::from_millis(200),
+            client.subscribe(
+                ExtractorIdentity::new(Chain::Ethereum, "vm:ambient"),
+                SubscriptionOptions::new().with_compression(false),
+            ),
+        )
+        .await
+        .expect("subscription timed out")
+        .expect("subscription failed");
+
+        let msg = timeout(Duration::from_millis(100), rx.recv())
             .await
-            .expect("subscription timed out")
-            .expect("subscription failed");
+            .expect("awaiting message timeout out")
+            .expect("receiving message failed");
+        assert!(msg.is_ok());
 
-            let _ = timeout(Duration::from_millis(100), rx.recv())
-                .await
-                .expect("awaiting message timeout out")
-                .expect("receiving message failed");
+        // The connection drops and the subscription is resumed on the next one, reporting the gap.
+        let gap = timeout(Duration::from_millis(500), rx.recv())
+            .await
+            .expect("awaiting resumed subscription timed out")
+            .expect("receiver closed on reconnect");
+        assert!(matches!(gap, Err(DeltasError::StreamGap)));
+        let msg = timeout(Duration::from_millis(100), rx.recv())
+            .await
+            .expect("awaiting message timeout out")
+            .expect("receiving message failed");
+        assert!(msg.is_ok());
 
-            // wait for the connection to drop
-            let res = timeout(Duration::from_millis(200), rx.recv())
-                .await
-                .expect("awaiting closed connection timeout out");
-            assert!(res.is_none());
-        }
+        // Once the client gives up reconnecting, the receiver is closed.
+        let res = timeout(Duration::from_millis(1000), rx.recv())
+            .await
+            .expect("awaiting closed connection timeout out");
+        assert!(res.is_none());
         let res = jh.await.expect("ws client join failed");
         // 5th client reconnect attempt should fail
         assert!(res.is_err());
@@ -1528,6 +2593,178 @@ mod tests {
             .expect("ws server loop errored");
     }
 
+    #[test_log::test(tokio::test)]
+    async fn test_connection_events() {
+        let exp_comm = [
+            ExpectedComm::Receive(
+                100,
+                tungstenite::protocol::Message::Text(
+                    SUBSCRIBE
+                        .to_owned()
+                        .replace(|c: char| c.is_whitespace(), ""),
+                ),
+            ),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(
+                SUBSCRIPTION_CONFIRMATION
+                    .to_owned()
+                    .replace(|c: char| c.is_whitespace(), ""),
+            )),
+        ];
+        let (addr, server_thread) = mock_tycho_ws(&exp_comm, 1).await;
+        let client = WsDeltasClient::new_with_reconnects(
+            &format!("ws://{addr}"),
+            None,
+            3,
+            // server stays down for 100ms on connection drop
+            Duration::from_millis(110),
+        )
+        .unwrap();
+        let mut events = client.connection_events();
+
+        let jh = client
+            .connect()
+            .await
+            .expect("connect failed");
+        let (_, mut rx) = timeout(
+            Duration::from_millis(200),
+            client.subscribe(
+                ExtractorIdentity::new(Chain::Ethereum, "vm:ambient"),
+                SubscriptionOptions::new().with_compression(false),
+            ),
+        )
+        .await
+        .expect("subscription timed out")
+        .expect("subscription failed");
+        // wait for the subscription to be resumed, then for the client to give up
+        let gap = timeout(Duration::from_millis(500), rx.recv())
+            .await
+            .expect("awaiting resumed subscription timed out");
+        assert!(matches!(gap, Some(Err(DeltasError::StreamGap))));
+        let res = timeout(Duration::from_millis(1000), rx.recv())
+            .await
+            .expect("awaiting closed connection timeout out");
+        assert!(res.is_none());
+        let res = jh.await.expect("ws client join failed");
+        assert!(res.is_err());
+        server_thread
+            .await
+            .expect("ws server loop errored");
+
+        let subscription_id =
+            Uuid::parse_str("30b740d1-cf09-4e0e-8cfe-b1434d447ece").expect("valid uuid");
+        let extractor_id = ExtractorIdentity::new(Chain::Ethereum, "vm:ambient");
+        let expected = vec![
+            ConnectionEvent::Connected,
+            ConnectionEvent::SubscriptionAdded {
+                extractor_id: extractor_id.clone(),
+                subscription_id,
+            },
+            ConnectionEvent::Disconnected,
+            ConnectionEvent::Reconnecting { attempt: 1 },
+            ConnectionEvent::Connected,
+            ConnectionEvent::SubscriptionResumed { extractor_id, subscription_id },
+            ConnectionEvent::Disconnected,
+            ConnectionEvent::Reconnecting { attempt: 2 },
+            ConnectionEvent::SubscriptionEnded { subscription_id },
+        ];
+        let mut received = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            received.push(event);
+        }
+        assert_eq!(received, expected);
+    }
+
+    #[tokio::test]
+    async fn test_resumed_subscription_keeps_subscriber_id() {
+        let resumed_id = "7a9c5d0e-3f1b-4c2a-9e8d-6b5f4a3c2d1e";
+        let compact = |msg: &str| msg.replace(|c: char| c.is_whitespace(), "");
+        let subscribe =
+            ExpectedComm::Receive(100, tungstenite::protocol::Message::Text(compact(SUBSCRIBE)));
+        let first_session = vec![
+            subscribe.clone(),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(compact(
+                SUBSCRIPTION_CONFIRMATION,
+            ))),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(BLOCK_DELTAS.to_owned())),
+        ];
+        // The server assigns a new id to the resumed subscription.
+        let with_resumed_id =
+            |msg: &str| msg.replace("30b740d1-cf09-4e0e-8cfe-b1434d447ece", resumed_id);
+        let second_session = vec![
+            subscribe,
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(compact(&with_resumed_id(
+                SUBSCRIPTION_CONFIRMATION,
+            )))),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(with_resumed_id(BLOCK_DELTAS))),
+            ExpectedComm::Receive(
+                500,
+                tungstenite::protocol::Message::Text(compact(&with_resumed_id(UNSUBSCRIBE))),
+            ),
+            ExpectedComm::Send(tungstenite::protocol::Message::Text(compact(&with_resumed_id(
+                SUBSCRIPTION_ENDED,
+            )))),
+        ];
+        let (addr, server_thread) =
+            mock_tycho_ws_sessions(vec![first_session, second_session]).await;
+        let client = WsDeltasClient::new_with_reconnects(
+            &format!("ws://{addr}"),
+            None,
+            3,
+            Duration::from_millis(10),
+        )
+        .unwrap();
+        let jh = client
+            .connect()
+            .await
+            .expect("connect failed");
+
+        let (subscription_id, mut rx) = timeout(
+            Duration::from_millis(200),
+            client.subscribe(
+                ExtractorIdentity::new(Chain::Ethereum, "vm:ambient"),
+                SubscriptionOptions::new().with_compression(false),
+            ),
+        )
+        .await
+        .expect("subscription timed out")
+        .expect("subscription failed");
+        let msg = timeout(Duration::from_millis(100), rx.recv())
+            .await
+            .expect("awaiting message timeout out")
+            .expect("receiving message failed");
+        assert!(msg.is_ok());
+
+        let gap = timeout(Duration::from_millis(500), rx.recv())
+            .await
+            .expect("awaiting resumed subscription timed out")
+            .expect("receiver closed on reconnect");
+        assert!(matches!(gap, Err(DeltasError::StreamGap)));
+        let msg = timeout(Duration::from_millis(100), rx.recv())
+            .await
+            .expect("awaiting message timeout out")
+            .expect("receiving message failed");
+        assert!(msg.is_ok());
+
+        // Unsubscribing with the original id ends the resumed subscription on the server.
+        timeout(Duration::from_millis(500), client.unsubscribe(subscription_id))
+            .await
+            .expect("unsubscribe timed out")
+            .expect("unsubscribe failed");
+        let res = timeout(Duration::from_millis(100), rx.recv())
+            .await
+            .expect("awaiting closed subscription timeout out");
+        assert!(res.is_none());
+
+        timeout(Duration::from_millis(100), client.close())
+            .await
+            .expect("close timed out")
+            .expect("close failed");
+        jh.await
+            .expect("ws loop errored")
+            .unwrap();
+        server_thread.await.unwrap();
+    }
+
     async fn mock_bad_connection_tycho_ws(accept_first: bool) -> (SocketAddr, JoinHandle<()>) {
         let server = TcpListener::bind("127.0.0.1:0")
             .await
@@ -1579,6 +2816,97 @@ mod tests {
         assert!(subscription_res.is_err());
     }
 
+    #[test(tokio::test)]
+    #[allow(clippy::result_large_err)]
+    async fn test_custom_handshake_headers() {
+        let server = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("localhost bind failed");
+        let addr = server.local_addr().unwrap();
+        let server_thread = tokio::spawn(async move {
+            let (stream, _) = server.accept().await.unwrap();
+            let mut api_key = None;
+            let _ws = tokio_tungstenite::accept_hdr_async(
+                stream,
+                |req: &tungstenite::handshake::server::Request,
+                 res: tungstenite::handshake::server::Response| {
+                    api_key = req
+                        .headers()
+                        .get("x-api-key")
+                        .map(|v| v.to_str().unwrap().to_string());
+                    Ok(res)
+                },
+            )
+            .await
+            .unwrap();
+            api_key
+        });
+
+        let client = WsDeltasClient::new(&format!("ws://{addr}"), None)
+            .unwrap()
+            .with_header("x-api-key", "secret");
+        let jh = client
+            .connect()
+            .await
+            .expect("connect failed");
+
+        let api_key = server_thread.await.unwrap();
+        assert_eq!(api_key.as_deref(), Some("secret"));
+        jh.abort();
+    }
+
+    #[test(tokio::test)]
+    async fn test_heartbeat_timeout_detects_dead_connection() {
+        // A server that completes the handshake but never sends or reads anything afterwards.
+        let server = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("localhost bind failed");
+        let addr = server.local_addr().unwrap();
+        let server_thread = tokio::spawn(async move {
+            let (stream, _) = server.accept().await.unwrap();
+            let _ws = tokio_tungstenite::accept_async(stream)
+                .await
+                .unwrap();
+            sleep(Duration::from_secs(5)).await;
+        });
+
+        let client =
+            WsDeltasClient::new_with_reconnects(&format!("ws://{addr}"), None, 1, Duration::ZERO)
+                .unwrap()
+                .with_heartbeat(Duration::from_millis(10), Duration::from_millis(50));
+        let jh = client
+            .connect()
+            .await
+            .expect("connect failed");
+
+        let res = timeout(Duration::from_secs(1), jh)
+            .await
+            .expect("dead connection was not detected")
+            .unwrap();
+
+        assert!(res.is_err());
+        server_thread.abort();
+    }
+
+    #[test(tokio::test)]
+    async fn test_connect_surfaces_connection_error() {
+        // Bind and drop a listener to get a local port nobody is listening on.
+        let addr = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("localhost bind failed")
+            .local_addr()
+            .unwrap();
+        let client =
+            WsDeltasClient::new_with_reconnects(&format!("ws://{addr}"), None, 2, Duration::ZERO)
+                .unwrap();
+
+        let res = timeout(Duration::from_secs(1), client.connect())
+            .await
+            .expect("connect timed out");
+
+        assert!(matches!(res, Err(DeltasError::ConnectionError(_))));
+    }
+
     #[test(tokio::test)]
     async fn test_ws_client_retry_cooldown() {
         let start = std::time::Instant::now();
@@ -1611,6 +2939,78 @@ mod tests {
         assert!(elapsed < Duration::from_millis(500), "Took too long: {:?}", elapsed);
     }
 
+    #[test]
+    fn test_ws_uri() {
+        let uri = |base: &str| WsDeltasClient::new(base, None).unwrap();
+
+        assert_eq!(uri("ws://127.0.0.1:4242").ws_uri(), "ws://127.0.0.1:4242/v1/ws");
+        assert_eq!(uri("ws://127.0.0.1:4242/").ws_uri(), "ws://127.0.0.1:4242/v1/ws");
+        assert_eq!(uri("ws://127.0.0.1:4242/tycho/").ws_uri(), "ws://127.0.0.1:4242/tycho/v1/ws");
+        assert_eq!(
+            uri("ws://127.0.0.1:4242")
+                .with_server_version("/api/v2/")
+                .with_ws_path("/stream")
+                .ws_uri(),
+            "ws://127.0.0.1:4242/api/v2/stream"
+        );
+        assert_eq!(
+            uri("ws://127.0.0.1:4242")
+                .with_server_version("")
+                .ws_uri(),
+            "ws://127.0.0.1:4242/ws"
+        );
+    }
+
+    #[test]
+    fn test_custom_buffer_sizes() {
+        let client = WsDeltasClient::new("ws://127.0.0.1:4242", None)
+            .unwrap()
+            .with_ws_buffer_size(16)
+            .with_subscription_buffer_size(1024);
+
+        assert_eq!(client.ws_buffer_size, 16);
+        assert_eq!(client.subscription_buffer_size, 1024);
+    }
+
+    #[test]
+    fn test_reconnect_cooldown_backoff() {
+        let client = WsDeltasClient::new_with_backoff(
+            "ws://127.0.0.1:4242",
+            None,
+            10,
+            Duration::from_millis(100),
+            Duration::from_millis(1000),
+        )
+        .unwrap();
+
+        let cooldowns: Vec<_> = (1..=6)
+            .map(|attempt| client.reconnect_cooldown(attempt))
+            .collect();
+
+        assert_eq!(
+            cooldowns,
+            [100, 200, 400, 800, 1000, 1000]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(client.reconnect_cooldown(u64::MAX), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_reconnect_cooldown_constant() {
+        let client = WsDeltasClient::new_with_reconnects(
+            "ws://127.0.0.1:4242",
+            None,
+            10,
+            Duration::from_millis(100),
+        )
+        .unwrap();
+
+        assert_eq!(client.reconnect_cooldown(1), Duration::from_millis(100));
+        assert_eq!(client.reconnect_cooldown(5), Duration::from_millis(100));
+    }
+
     #[test_log::test(tokio::test)]
     async fn test_buffer_full_triggers_unsubscribe() {
         // Expected communication sequence for buffer full scenario
@@ -1774,7 +3174,7 @@ mod tests {
             received_msgs.len()
         );
 
-        if let Some(first_msg) = received_msgs.first() {
+        if let Some(Ok(first_msg)) = received_msgs.first() {
             assert_eq!(first_msg.block.number, 123, "Expected first message with block 123");
         }
 
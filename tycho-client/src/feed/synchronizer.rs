@@ -366,139 +366,153 @@ where
 
         let result = async {
             info!("Waiting for deltas...");
-            let mut warned = false;
-            let mut first_msg = loop {
-                let msg = select! {
-                    deltas_result = timeout(Duration::from_secs(self.timeout), msg_rx.recv()) => {
-                        deltas_result
-                            .map_err(|_| {
-                                SynchronizerError::Timeout(format!(
-                                    "First deltas took longer than {t}s to arrive",
-                                    t = self.timeout
-                                ))
-                            })?
-                            .ok_or_else(|| {
-                                SynchronizerError::ConnectionError(
-                                    "Deltas channel closed before first message".to_string(),
-                                )
-                            })?
-                    },
-                    _ = &mut end_rx => {
-                        info!("Received close signal while waiting for first deltas");
-                        return Ok(());
+            'resync: loop {
+                let mut warned = false;
+                let mut first_msg = loop {
+                    let msg = select! {
+                        deltas_result = timeout(Duration::from_secs(self.timeout), msg_rx.recv()) => {
+                            deltas_result
+                                .map_err(|_| {
+                                    SynchronizerError::Timeout(format!(
+                                        "First deltas took longer than {t}s to arrive",
+                                        t = self.timeout
+                                    ))
+                                })?
+                                .ok_or_else(|| {
+                                    SynchronizerError::ConnectionError(
+                                        "Deltas channel closed before first message".to_string(),
+                                    )
+                                })?
+                        },
+                        _ = &mut end_rx => {
+                            info!("Received close signal while waiting for first deltas");
+                            return Ok(());
+                        }
+                    };
+                    let msg = match msg {
+                        // Nothing was synced from the interrupted stream yet, keep waiting.
+                        Err(DeltasError::StreamGap) => continue,
+                        msg => msg?,
+                    };
+
+                    let incoming = BlockHeader::from_block(msg.get_block(), msg.is_revert());
+                    if let Some(current) = &self.last_synced_block {
+                        if current.number >= incoming.number && !self.is_next_expected(&incoming) {
+                            if !warned {
+                                info!(extractor=%self.extractor_id, from=incoming.number, to=current.number, "Syncing. Skipping messages");
+                                warned = true;
+                            }
+                            continue
+                        }
                     }
+                    break msg;
                 };
 
-                let incoming = BlockHeader::from_block(msg.get_block(), msg.is_revert());
-                if let Some(current) = &self.last_synced_block {
-                    if current.number >= incoming.number && !self.is_next_expected(&incoming) {
-                        if !warned {
-                            info!(extractor=%self.extractor_id, from=incoming.number, to=current.number, "Syncing. Skipping messages");
-                            warned = true;
-                        }
-                        continue
-                    }
-                }
-                break msg;
-            };
-
-            self.filter_deltas(&mut first_msg);
-
-            // initial snapshot
-            let block = first_msg.get_block().clone();
-            info!(height = &block.number, "First deltas received");
-            let header = BlockHeader::from_block(first_msg.get_block(), first_msg.is_revert());
-            let deltas_msg = StateSyncMessage {
-                header: BlockHeader::from_block(first_msg.get_block(), first_msg.is_revert()),
-                snapshots: Default::default(),
-                deltas: Some(first_msg),
-                removed_components: Default::default(),
-            };
-
-            // If possible skip retrieving snapshots
-            let msg = if !self.is_next_expected(&header) {
-                info!("Retrieving snapshot");
-                let snapshot = self
-                    .get_snapshots::<Vec<&String>>(
-                        BlockHeader::from_block(&block, false),
-                        None,
-                    )
-                    .await?
-                    .merge(deltas_msg);
-                let n_components = self.component_tracker.components.len();
-                let n_snapshots = snapshot.snapshots.states.len();
-                info!(n_components, n_snapshots, "Initial snapshot retrieved, starting delta message feed");
-                snapshot
-            } else {
-                deltas_msg
-            };
-            block_tx.send(Ok(msg)).await?;
-            self.last_synced_block = Some(header.clone());
-            loop {
-                select! {
-                    deltas_opt = msg_rx.recv() => {
-                        if let Some(mut deltas) = deltas_opt {
-                            let header = BlockHeader::from_block(deltas.get_block(), deltas.is_revert());
-                            debug!(block_number=?header.number, "Received delta message");
-
-                            let (snapshots, removed_components) = {
-                                // 1. Remove components based on latest changes
-                                // 2. Add components based on latest changes, query those for snapshots
-                                let (to_add, to_remove) = self.component_tracker.filter_updated_components(&deltas);
-
-                                // Only components we don't track yet need a snapshot,
-                                let requiring_snapshot: Vec<_> = to_add
-                                    .iter()
-                                    .filter(|id| {
-                                        !self.component_tracker
-                                            .components
-                                            .contains_key(id.as_str())
-                                    })
-                                    .collect();
-                                debug!(components=?requiring_snapshot, "SnapshotRequest");
-                                self.component_tracker
-                                    .start_tracking(requiring_snapshot.as_slice())
-                                    .await?;
-
-                                let snapshots = self
-                                    .get_snapshots(header.clone(), Some(requiring_snapshot))
-                                    .await?
-                                    .snapshots;
-
-                                let removed_components = if !to_remove.is_empty() {
-                                    self.component_tracker.stop_tracking(&to_remove)
-                                } else {
-                                    Default::default()
-                                };
+                self.filter_deltas(&mut first_msg);
+
+                // initial snapshot
+                let block = first_msg.get_block().clone();
+                info!(height = &block.number, "First deltas received");
+                let header = BlockHeader::from_block(first_msg.get_block(), first_msg.is_revert());
+                let deltas_msg = StateSyncMessage {
+                    header: BlockHeader::from_block(first_msg.get_block(), first_msg.is_revert()),
+                    snapshots: Default::default(),
+                    deltas: Some(first_msg),
+                    removed_components: Default::default(),
+                };
 
-                                (snapshots, removed_components)
-                            };
+                // If possible skip retrieving snapshots
+                let msg = if !self.is_next_expected(&header) {
+                    info!("Retrieving snapshot");
+                    let snapshot = self
+                        .get_snapshots::<Vec<&String>>(
+                            BlockHeader::from_block(&block, false),
+                            None,
+                        )
+                        .await?
+                        .merge(deltas_msg);
+                    let n_components = self.component_tracker.components.len();
+                    let n_snapshots = snapshot.snapshots.states.len();
+                    info!(n_components, n_snapshots, "Initial snapshot retrieved, starting delta message feed");
+                    snapshot
+                } else {
+                    deltas_msg
+                };
+                block_tx.send(Ok(msg)).await?;
+                self.last_synced_block = Some(header.clone());
+                loop {
+                    select! {
+                        deltas_opt = msg_rx.recv() => {
+                            if let Some(deltas_result) = deltas_opt {
+                                let mut deltas = match deltas_result {
+                                    Err(DeltasError::StreamGap) => {
+                                        warn!(extractor=%self.extractor_id, "Missed deltas during a reconnect, resyncing");
+                                        continue 'resync
+                                    }
+                                    deltas_result => deltas_result?,
+                                };
+                                let header = BlockHeader::from_block(deltas.get_block(), deltas.is_revert());
+                                debug!(block_number=?header.number, "Received delta message");
+
+                                let (snapshots, removed_components) = {
+                                    // 1. Remove components based on latest changes
+                                    // 2. Add components based on latest changes, query those for snapshots
+                                    let (to_add, to_remove) = self.component_tracker.filter_updated_components(&deltas);
+
+                                    // Only components we don't track yet need a snapshot,
+                                    let requiring_snapshot: Vec<_> = to_add
+                                        .iter()
+                                        .filter(|id| {
+                                            !self.component_tracker
+                                                .components
+                                                .contains_key(id.as_str())
+                                        })
+                                        .collect();
+                                    debug!(components=?requiring_snapshot, "SnapshotRequest");
+                                    self.component_tracker
+                                        .start_tracking(requiring_snapshot.as_slice())
+                                        .await?;
+
+                                    let snapshots = self
+                                        .get_snapshots(header.clone(), Some(requiring_snapshot))
+                                        .await?
+                                        .snapshots;
+
+                                    let removed_components = if !to_remove.is_empty() {
+                                        self.component_tracker.stop_tracking(&to_remove)
+                                    } else {
+                                        Default::default()
+                                    };
+
+                                    (snapshots, removed_components)
+                                };
 
-                            // 3. Update entrypoints on the tracker (affects which contracts are tracked)
-                            self.component_tracker.process_entrypoints(&deltas.dci_update);
+                                // 3. Update entrypoints on the tracker (affects which contracts are tracked)
+                                self.component_tracker.process_entrypoints(&deltas.dci_update);
 
-                            // 4. Filter deltas by currently tracked components / contracts
-                            self.filter_deltas(&mut deltas);
-                            let n_changes = deltas.n_changes();
+                                // 4. Filter deltas by currently tracked components / contracts
+                                self.filter_deltas(&mut deltas);
+                                let n_changes = deltas.n_changes();
 
-                            // 5. Send the message
-                            let next = StateSyncMessage {
-                                header: header.clone(),
-                                snapshots,
-                                deltas: Some(deltas),
-                                removed_components,
-                            };
-                            block_tx.send(Ok(next)).await?;
-                            self.last_synced_block = Some(header.clone());
+                                // 5. Send the message
+                                let next = StateSyncMessage {
+                                    header: header.clone(),
+                                    snapshots,
+                                    deltas: Some(deltas),
+                                    removed_components,
+                                };
+                                block_tx.send(Ok(next)).await?;
+                                self.last_synced_block = Some(header.clone());
 
-                            debug!(block_number=?header.number, n_changes, "Finished processing delta message");
-                        } else {
-                            return Err(SynchronizerError::ConnectionError("Deltas channel closed".to_string()));
+                                debug!(block_number=?header.number, n_changes, "Finished processing delta message");
+                            } else {
+                                return Err(SynchronizerError::ConnectionError("Deltas channel closed".to_string()));
+                            }
+                        },
+                        _ = &mut end_rx => {
+                            info!("Received close signal during state_sync");
+                            return Ok(());
                         }
-                    },
-                    _ = &mut end_rx => {
-                        info!("Received close signal during state_sync");
-                        return Ok(());
                     }
                 }
             }
@@ -663,7 +677,7 @@ mod test {
     use test_log::test;
     use tycho_common::dto::{
         AddressStorageLocation, Block, Chain, ComponentTvlRequestBody, ComponentTvlRequestResponse,
-        DCIUpdate, EntryPoint, PaginationResponse, ProtocolComponentRequestResponse,
+        DCIUpdate, EntryPoint, Health, PaginationResponse, ProtocolComponentRequestResponse,
         ProtocolComponentsRequestBody, ProtocolStateRequestBody, ProtocolStateRequestResponse,
         ProtocolSystemsRequestBody, ProtocolSystemsRequestResponse, RPCTracerParams,
         StateRequestBody, StateRequestResponse, TokensRequestBody, TokensRequestResponse,
@@ -757,6 +771,10 @@ mod test {
                 .await
         }
 
+        async fn get_health(&self) -> Result<Health, RPCError> {
+            self.0.get_health().await
+        }
+
         fn compression(&self) -> bool {
             self.0.compression()
         }
@@ -781,7 +799,7 @@ mod test {
             &self,
             extractor_id: ExtractorIdentity,
             options: SubscriptionOptions,
-        ) -> Result<(Uuid, Receiver<BlockChanges>), DeltasError> {
+        ) -> Result<(Uuid, Receiver<Result<BlockChanges, DeltasError>>), DeltasError> {
             self.0
                 .subscribe(extractor_id, options)
                 .await
@@ -1240,7 +1258,8 @@ mod test {
         assert_eq!(snap, exp);
     }
 
-    fn mock_clients_for_state_sync() -> (MockRPCClient, MockDeltasClient, Sender<BlockChanges>) {
+    fn mock_clients_for_state_sync(
+    ) -> (MockRPCClient, MockDeltasClient, Sender<Result<BlockChanges, DeltasError>>) {
         let mut rpc_client = make_mock_client();
         // Mocks for the start_tracking call, these need to come first because they are more
         // specific, see: https://docs.rs/mockall/latest/mockall/#matching-multiple-calls
@@ -1475,14 +1494,14 @@ mod test {
         // Test starts here
         let (handle, mut rx) = state_sync.start().await;
         let (jh, close_tx) = handle.split();
-        tx.send(deltas[0].clone())
+        tx.send(Ok(deltas[0].clone()))
             .await
             .expect("deltas channel msg 0 closed!");
         let first_msg = timeout(Duration::from_millis(100), rx.recv())
             .await
             .expect("waiting for first state msg timed out!")
             .expect("state sync block sender closed!");
-        tx.send(deltas[1].clone())
+        tx.send(Ok(deltas[1].clone()))
             .await
             .expect("deltas channel msg 1 closed!");
         let second_msg = timeout(Duration::from_millis(100), rx.recv())
@@ -1608,6 +1627,66 @@ mod test {
         assert_eq!(second_msg.unwrap(), exp2);
     }
 
+    #[test(tokio::test)]
+    async fn test_state_sync_resyncs_after_stream_gap() {
+        let (rpc_client, deltas_client, tx) = mock_clients_for_state_sync();
+        let block = |number: u64| BlockChanges {
+            extractor: "uniswap-v2".to_string(),
+            chain: Chain::Ethereum,
+            block: Block {
+                number,
+                hash: Bytes::from(number),
+                parent_hash: Bytes::from(number - 1),
+                chain: Chain::Ethereum,
+                ts: Default::default(),
+            },
+            revert: false,
+            ..Default::default()
+        };
+        let mut state_sync = with_mocked_clients(true, true, Some(rpc_client), Some(deltas_client));
+        state_sync
+            .initialize()
+            .await
+            .expect("Init failed");
+
+        let (handle, mut rx) = state_sync.start().await;
+        let (jh, close_tx) = handle.split();
+        tx.send(Ok(block(1)))
+            .await
+            .expect("deltas channel msg 0 closed!");
+        let first_msg = timeout(Duration::from_millis(100), rx.recv())
+            .await
+            .expect("waiting for first state msg timed out!")
+            .expect("state sync block sender closed!")
+            .expect("no error");
+        // Block 2 was lost while the websocket reconnected.
+        tx.send(Err(DeltasError::StreamGap))
+            .await
+            .expect("deltas channel gap closed!");
+        tx.send(Ok(block(3)))
+            .await
+            .expect("deltas channel msg 1 closed!");
+        let second_msg = timeout(Duration::from_millis(100), rx.recv())
+            .await
+            .expect("waiting for resynced state msg timed out!")
+            .expect("state sync block sender closed!")
+            .expect("no error");
+        let _ = close_tx.send(());
+        jh.await
+            .expect("state sync task panicked!");
+
+        assert_eq!(first_msg.header.number, 1);
+        assert_eq!(second_msg.header.number, 3);
+        // A fresh snapshot is taken instead of applying deltas on top of the gap.
+        assert_eq!(
+            second_msg
+                .snapshots
+                .states
+                .keys()
+                .collect::<HashSet<_>>(),
+            HashSet::from([&"Component1".to_string(), &"Component2".to_string()])
+        );
+    }
     #[test(tokio::test)]
     async fn test_state_sync_with_tvl_range() {
         // Define the range for testing
@@ -1807,7 +1886,7 @@ mod test {
         let (jh, close_tx) = handle.split();
 
         // Simulate sending delta messages
-        tx.send(deltas[0].clone())
+        tx.send(Ok(deltas[0].clone()))
             .await
             .expect("deltas channel msg 0 closed!");
 
@@ -1818,7 +1897,7 @@ mod test {
             .expect("state sync block sender closed!");
 
         // Send the third message, which should trigger TVL-based changes
-        tx.send(deltas[1].clone())
+        tx.send(Ok(deltas[1].clone()))
             .await
             .expect("deltas channel msg 1 closed!");
         let second_msg = timeout(Duration::from_millis(100), rx.recv())
@@ -2025,7 +2104,7 @@ mod test {
                 };
 
                 tokio::spawn(async move {
-                    let _ = tx.send(delta).await;
+                    let _ = tx.send(Ok(delta)).await;
                     // Close the channel after sending one message
                 });
 
@@ -2221,7 +2300,7 @@ mod test {
                 };
 
                 tokio::spawn(async move {
-                    let _ = tx.send(first_delta).await;
+                    let _ = tx.send(Ok(first_delta)).await;
                     // Keep the sender alive but don't send more messages
                     // This will make the recv() block waiting for the next message
                     tokio::time::sleep(Duration::from_secs(30)).await;
@@ -2469,7 +2548,7 @@ mod test {
                 };
 
                 tokio::spawn(async move {
-                    let _ = tx.send(expected_next_delta).await;
+                    let _ = tx.send(Ok(expected_next_delta)).await;
                 });
 
                 Ok((Uuid::default(), rx))
@@ -2675,7 +2754,7 @@ mod test {
 
                 tokio::spawn(async move {
                     for message in old_messages {
-                        let _ = tx.send(message).await;
+                        let _ = tx.send(Ok(message)).await;
                         tokio::time::sleep(Duration::from_millis(10)).await;
                     }
                 });
@@ -5,7 +5,10 @@
 //! queries, especially querying snapshots of data.
 use std::{
     collections::HashMap,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
     time::{Duration, SystemTime},
 };
 
@@ -26,12 +29,12 @@ use tracing::{debug, error, instrument, trace, warn};
 use tycho_common::{
     dto::{
         BlockParam, Chain, ComponentTvlRequestBody, ComponentTvlRequestResponse,
-        EntryPointWithTracingParams, PaginationLimits, PaginationParams, PaginationResponse,
-        ProtocolComponent, ProtocolComponentRequestResponse, ProtocolComponentsRequestBody,
-        ProtocolStateRequestBody, ProtocolStateRequestResponse, ProtocolSystemsRequestBody,
-        ProtocolSystemsRequestResponse, ResponseToken, StateRequestBody, StateRequestResponse,
-        TokensRequestBody, TokensRequestResponse, TracedEntryPointRequestBody,
-        TracedEntryPointRequestResponse, TracingResult, VersionParam,
+        EntryPointWithTracingParams, Health, PaginationLimits, PaginationParams,
+        PaginationResponse, ProtocolComponent, ProtocolComponentRequestResponse,
+        ProtocolComponentsRequestBody, ProtocolStateRequestBody, ProtocolStateRequestResponse,
+        ProtocolSystemsRequestBody, ProtocolSystemsRequestResponse, ResponseToken,
+        StateRequestBody, StateRequestResponse, TokensRequestBody, TokensRequestResponse,
+        TracedEntryPointRequestBody, TracedEntryPointRequestResponse, TracingResult, VersionParam,
     },
     models::ComponentId,
     Bytes,
@@ -137,6 +140,14 @@ pub enum RPCError {
 
     #[error("Server unreachable: {0}")]
     ServerUnreachable(String),
+
+    /// The server responded with an unexpected, non-success status code.
+    #[error("Server responded with status {status}: {body}")]
+    HttpStatus { status: StatusCode, body: String },
+
+    /// The request did not complete within the configured timeout.
+    #[error("Request timed out: {0}")]
+    Timeout(String),
 }
 
 #[cfg_attr(test, automock)]
@@ -213,6 +224,31 @@ pub trait RPCClient: Send + Sync {
         })
     }
 
+    /// Retrieves contract state snapshots for several independent requests.
+    ///
+    /// Requests are issued concurrently, at most `concurrency` at a time. Responses are returned
+    /// in the same order as the requests. Fails if any of the requests fails.
+    async fn get_contract_states_batch(
+        &self,
+        requests: &[StateRequestBody],
+        concurrency: usize,
+    ) -> Result<Vec<StateRequestResponse>, RPCError> {
+        let semaphore = Arc::new(Semaphore::new(concurrency));
+
+        let tasks = requests.iter().map(|body| {
+            let sem = semaphore.clone();
+            async move {
+                let _permit = sem
+                    .acquire()
+                    .await
+                    .map_err(|_| RPCError::Fatal("Semaphore dropped".to_string()))?;
+                self.get_contract_state(body).await
+            }
+        });
+
+        try_join_all(tasks).await
+    }
+
     async fn get_protocol_components(
         &self,
         request: &ProtocolComponentsRequestBody,
@@ -737,16 +773,42 @@ pub trait RPCClient: Send + Sync {
         chunk_size: Option<usize>,
         concurrency: usize,
     ) -> Result<Snapshot, RPCError>;
+
+    /// Checks whether the server is reachable and ready to serve requests.
+    ///
+    /// Unlike other requests this is not retried, so it can be used to fail fast.
+    async fn get_health(&self) -> Result<Health, RPCError>;
 }
 
+/// Default timeout for a single request attempt.
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
 /// Configuration options for HttpRPCClient
 #[derive(Debug, Clone)]
 pub struct HttpRPCClientOptions {
     /// Optional API key for authentication
     pub auth_key: Option<String>,
     /// Enable compression for requests (default: true)
-    /// When enabled, adds Accept-Encoding: zstd header
+    /// When enabled, adds Accept-Encoding: gzip, deflate, zstd header and transparently
+    /// decompresses responses according to their Content-Encoding
     pub compression: bool,
+    /// Optional timeout for each individual request attempt (default: 30s)
+    /// Timed out attempts are retried according to the backoff policy.
+    pub timeout: Option<Duration>,
+    /// Additional headers sent with every request, e.g. for authenticating gateways
+    pub headers: Vec<(String, String)>,
+    /// Maximum number of retries for transient failures (default: none)
+    /// If unset, retries are only bounded by the total time spent retrying.
+    pub max_retries: Option<u32>,
+    /// Delay before the first retry, grows exponentially with each retry (default: 250ms)
+    pub retry_base_delay: Duration,
+    /// Server api version, used as path prefix for all endpoints (default: v1)
+    /// Can also include a mount path, e.g. `api/v2`. Pass an empty string for unversioned
+    /// servers.
+    pub server_version: String,
+    /// Reject contract state requests without contract ids instead of fetching all contracts
+    /// (default: true)
+    pub require_explicit_ids: bool,
 }
 
 impl Default for HttpRPCClientOptions {
@@ -756,9 +818,18 @@ impl Default for HttpRPCClientOptions {
 }
 
 impl HttpRPCClientOptions {
-    /// Create new options with default values (compression enabled)
+    /// Create new options with default values (compression enabled, 30s request timeout)
     pub fn new() -> Self {
-        Self { auth_key: None, compression: true }
+        Self {
+            auth_key: None,
+            compression: true,
+            timeout: Some(DEFAULT_REQUEST_TIMEOUT),
+            headers: Vec::new(),
+            max_retries: None,
+            retry_base_delay: Duration::from_millis(250),
+            server_version: TYCHO_SERVER_VERSION.to_string(),
+            require_explicit_ids: true,
+        }
     }
 
     /// Set the authentication key
@@ -772,6 +843,50 @@ impl HttpRPCClientOptions {
         self.compression = compression;
         self
     }
+
+    /// Set the timeout for each request attempt (default: 30s)
+    ///
+    /// Pass `None` to disable the timeout.
+    pub fn with_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Add a header that is sent with every request
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Set the maximum number of retries for transient failures
+    ///
+    /// Transient failures are connection errors, timeouts, rate limits and 5xx responses.
+    pub fn with_max_retries(mut self, max_retries: Option<u32>) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the delay before the first retry (default: 250ms)
+    pub fn with_retry_base_delay(mut self, delay: Duration) -> Self {
+        self.retry_base_delay = delay;
+        self
+    }
+
+    /// Set the server api version used as path prefix for all endpoints (default: v1)
+    pub fn with_server_version(mut self, server_version: &str) -> Self {
+        self.server_version = server_version.to_string();
+        self
+    }
+
+    /// Set whether contract state requests must specify contract ids (default: true)
+    ///
+    /// Disable to allow requesting the state of all contracts of a protocol system at once. Servers
+    /// started with `--rpc-require-contract-ids` still reject such requests.
+    pub fn with_require_explicit_ids(mut self, require_explicit_ids: bool) -> Self {
+        self.require_explicit_ids = require_explicit_ids;
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -780,8 +895,11 @@ pub struct HttpRPCClient {
     url: Url,
     retry_after: Arc<RwLock<Option<SystemTime>>>,
     backoff_policy: ExponentialBackoff,
+    max_retries: Option<u32>,
     server_restart_duration: Duration,
     compression: bool,
+    server_version: String,
+    require_explicit_ids: bool,
 }
 
 impl HttpRPCClient {
@@ -809,13 +927,31 @@ impl HttpRPCClient {
             headers.insert(header::AUTHORIZATION, auth_value);
         }
 
+        for (name, value) in &options.headers {
+            let name = header::HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| RPCError::FormatRequest(format!("Invalid header name {name}: {e}")))?;
+            let mut value = header::HeaderValue::from_str(value).map_err(|e| {
+                RPCError::FormatRequest(format!("Invalid value for header {name}: {e}"))
+            })?;
+            // Custom headers usually carry credentials, keep them out of debug output.
+            value.set_sensitive(true);
+            headers.insert(name, value);
+        }
+
         let mut client_builder = ClientBuilder::new()
             .default_headers(headers)
             .http2_prior_knowledge();
 
         // When compression is disabled, turn off all automatic compression
         if !options.compression {
-            client_builder = client_builder.no_zstd();
+            client_builder = client_builder
+                .no_zstd()
+                .no_gzip()
+                .no_deflate();
+        }
+
+        if let Some(timeout) = options.timeout {
+            client_builder = client_builder.timeout(timeout);
         }
 
         let client = client_builder
@@ -827,7 +963,7 @@ impl HttpRPCClient {
             url: uri,
             retry_after: Arc::new(RwLock::new(None)),
             backoff_policy: ExponentialBackoffBuilder::new()
-                .with_initial_interval(Duration::from_millis(250))
+                .with_initial_interval(options.retry_base_delay)
                 // increase backoff time by 75% each failure
                 .with_multiplier(1.75)
                 // keep retrying every 30s
@@ -835,8 +971,11 @@ impl HttpRPCClient {
                 // if all retries take longer than 2m, give up
                 .with_max_elapsed_time(Some(Duration::from_secs(125)))
                 .build(),
+            max_retries: options.max_retries,
             server_restart_duration: Duration::from_secs(120),
             compression: options.compression,
+            server_version: options.server_version,
+            require_explicit_ids: options.require_explicit_ids,
         })
     }
 
@@ -853,11 +992,25 @@ impl HttpRPCClient {
         self
     }
 
+    /// Full uri of the given endpoint: `{url}/{server_version}/{endpoint}`.
+    ///
+    /// Leading and trailing slashes of the individual parts are normalised, empty parts are
+    /// skipped.
+    fn endpoint(&self, endpoint: &str) -> String {
+        let base = self.url.to_string();
+        [self.server_version.as_str(), endpoint]
+            .into_iter()
+            .map(|part| part.trim_matches('/'))
+            .filter(|part| !part.is_empty())
+            .fold(base.trim_end_matches('/').to_string(), |uri, part| format!("{uri}/{part}"))
+    }
+
     /// Converts a error response to a Result.
     ///
     /// Raises an error if the response status code id 429, 502, 503 or 504. In the 429
     /// case it will try to look for a retry-after header an parse it accordingly. The
-    /// parsed value is then passed as part of the error.
+    /// parsed value is then passed as part of the error. Any other non-success status is
+    /// raised as `HttpStatus` together with the response body.
     async fn error_for_response(
         &self,
         response: reqwest::Response,
@@ -880,6 +1033,13 @@ impl HttpRPCClient {
                     .await
                     .unwrap_or_else(|_| "Server Unreachable".to_string()),
             )),
+            status if !status.is_success() => Err(RPCError::HttpStatus {
+                status,
+                body: response
+                    .text()
+                    .await
+                    .unwrap_or_default(),
+            }),
             _ => Ok(response),
         }
     }
@@ -908,7 +1068,10 @@ impl HttpRPCClient {
                     e.into()
                 }
             }
-            RPCError::RateLimited(None) => e.into(),
+            RPCError::RateLimited(None) | RPCError::HttpClient(..) | RPCError::Timeout(_) => {
+                e.into()
+            }
+            RPCError::HttpStatus { status, .. } if status.is_server_error() => e.into(),
             _ => backoff::Error::permanent(e),
         }
     }
@@ -931,33 +1094,64 @@ impl HttpRPCClient {
     /// Makes a post request handling transient failures.
     ///
     /// If a retry-after header is received it will be respected. Else the configured
-    /// backoff policy is used to deal with transient network or server errors.
+    /// backoff policy is used to deal with transient network or server errors, giving up
+    /// once the maximum number of retries, if any, is exhausted.
     async fn make_post_request<T: Serialize + ?Sized>(
         &self,
         request: &T,
         uri: &String,
     ) -> Result<Response, RPCError> {
         self.wait_until_retry_after().await;
+        let attempts = AtomicU32::new(0);
         let response = backoff::future::retry(self.backoff_policy.clone(), || async {
-            let server_response = self
-                .http_client
-                .post(uri)
-                .json(request)
-                .send()
-                .await
-                .map_err(|e| RPCError::HttpClient(e.to_string(), e))?;
-
-            match self
-                .error_for_response(server_response)
+            let attempt = attempts.fetch_add(1, Ordering::Relaxed);
+            let retries_exhausted = self
+                .max_retries
+                .is_some_and(|max_retries| attempt >= max_retries);
+            self.send_post_request(request, uri)
                 .await
-            {
-                Ok(response) => Ok(response),
-                Err(e) => Err(self.handle_error_for_backoff(e).await),
-            }
+                .map_err(|e| match e {
+                    backoff::Error::Transient { err, .. } if retries_exhausted => {
+                        backoff::Error::permanent(err)
+                    }
+                    e => e,
+                })
         })
         .await?;
         Ok(response)
     }
+
+    /// Sends a single post request and classifies any failure for the backoff policy.
+    async fn send_post_request<T: Serialize + ?Sized>(
+        &self,
+        request: &T,
+        uri: &String,
+    ) -> Result<Response, backoff::Error<RPCError>> {
+        let server_response = self
+            .http_client
+            .post(uri)
+            .json(request)
+            .send()
+            .await
+            .map_err(send_error);
+
+        match server_response {
+            Ok(response) => match self.error_for_response(response).await {
+                Ok(response) => Ok(response),
+                Err(e) => Err(self.handle_error_for_backoff(e).await),
+            },
+            Err(e) => Err(self.handle_error_for_backoff(e).await),
+        }
+    }
+}
+
+/// Maps an error from sending a request, distinguishing timeouts from other client errors.
+fn send_error(e: reqwest::Error) -> RPCError {
+    if e.is_timeout() {
+        RPCError::Timeout(e.to_string())
+    } else {
+        RPCError::HttpClient(e.to_string(), e)
+    }
 }
 
 fn parse_retry_value(val: &str) -> Option<SystemTime> {
@@ -987,16 +1181,13 @@ impl RPCClient for HttpRPCClient {
             .as_ref()
             .is_none_or(|ids| ids.is_empty())
         {
+            if self.require_explicit_ids {
+                return Err(RPCError::FormatRequest("no contract ids specified".to_string()));
+            }
             warn!("No contract ids specified in request.");
         }
 
-        let uri = format!(
-            "{}/{}/contract_state",
-            self.url
-                .to_string()
-                .trim_end_matches('/'),
-            TYCHO_SERVER_VERSION
-        );
+        let uri = self.endpoint("contract_state");
         debug!(%uri, "Sending contract_state request to Tycho server");
         trace!(?request, "Sending request to Tycho server");
         let response = self
@@ -1031,13 +1222,7 @@ impl RPCClient for HttpRPCClient {
         &self,
         request: &ProtocolComponentsRequestBody,
     ) -> Result<ProtocolComponentRequestResponse, RPCError> {
-        let uri = format!(
-            "{}/{}/protocol_components",
-            self.url
-                .to_string()
-                .trim_end_matches('/'),
-            TYCHO_SERVER_VERSION,
-        );
+        let uri = self.endpoint("protocol_components");
         debug!(%uri, "Sending protocol_components request to Tycho server");
         trace!(?request, "Sending request to Tycho server");
 
@@ -1071,13 +1256,7 @@ impl RPCClient for HttpRPCClient {
             warn!("No protocol ids specified in request.");
         }
 
-        let uri = format!(
-            "{}/{}/protocol_state",
-            self.url
-                .to_string()
-                .trim_end_matches('/'),
-            TYCHO_SERVER_VERSION
-        );
+        let uri = self.endpoint("protocol_state");
         debug!(%uri, "Sending protocol_states request to Tycho server");
         trace!(?request, "Sending request to Tycho server");
 
@@ -1114,13 +1293,7 @@ impl RPCClient for HttpRPCClient {
         &self,
         request: &TokensRequestBody,
     ) -> Result<TokensRequestResponse, RPCError> {
-        let uri = format!(
-            "{}/{}/tokens",
-            self.url
-                .to_string()
-                .trim_end_matches('/'),
-            TYCHO_SERVER_VERSION
-        );
+        let uri = self.endpoint("tokens");
         debug!(%uri, "Sending tokens request to Tycho server");
 
         let response = self
@@ -1141,13 +1314,7 @@ impl RPCClient for HttpRPCClient {
         &self,
         request: &ProtocolSystemsRequestBody,
     ) -> Result<ProtocolSystemsRequestResponse, RPCError> {
-        let uri = format!(
-            "{}/{}/protocol_systems",
-            self.url
-                .to_string()
-                .trim_end_matches('/'),
-            TYCHO_SERVER_VERSION
-        );
+        let uri = self.endpoint("protocol_systems");
         debug!(%uri, "Sending protocol_systems request to Tycho server");
         trace!(?request, "Sending request to Tycho server");
         let response = self
@@ -1168,13 +1335,7 @@ impl RPCClient for HttpRPCClient {
         &self,
         request: &ComponentTvlRequestBody,
     ) -> Result<ComponentTvlRequestResponse, RPCError> {
-        let uri = format!(
-            "{}/{}/component_tvl",
-            self.url
-                .to_string()
-                .trim_end_matches('/'),
-            TYCHO_SERVER_VERSION
-        );
+        let uri = self.endpoint("component_tvl");
         debug!(%uri, "Sending get_component_tvl request to Tycho server");
         trace!(?request, "Sending request to Tycho server");
         let response = self
@@ -1198,12 +1359,7 @@ impl RPCClient for HttpRPCClient {
         &self,
         request: &TracedEntryPointRequestBody,
     ) -> Result<TracedEntryPointRequestResponse, RPCError> {
-        let uri = format!(
-            "{}/{TYCHO_SERVER_VERSION}/traced_entry_points",
-            self.url
-                .to_string()
-                .trim_end_matches('/')
-        );
+        let uri = self.endpoint("traced_entry_points");
         debug!(%uri, "Sending traced_entry_points request to Tycho server");
         trace!(?request, "Sending request to Tycho server");
 
@@ -1226,6 +1382,29 @@ impl RPCClient for HttpRPCClient {
         Ok(entrypoints)
     }
 
+    #[instrument(skip(self))]
+    async fn get_health(&self) -> Result<Health, RPCError> {
+        let uri = self.endpoint("health");
+        debug!(%uri, "Sending health request to Tycho server");
+        let response = self
+            .http_client
+            .get(&uri)
+            .send()
+            .await
+            .map_err(send_error)?;
+        let response = self
+            .error_for_response(response)
+            .await?;
+        let body = response
+            .text()
+            .await
+            .map_err(|e| RPCError::ParseResponse(e.to_string()))?;
+        let health = serde_json::from_str::<Health>(&body)
+            .map_err(|err| RPCError::ParseResponse(format!("Error: {err}, Body: {body}")))?;
+        trace!(?health, "Received health response from Tycho server");
+        Ok(health)
+    }
+
     async fn get_snapshots<'a>(
         &self,
         request: &SnapshotParameters<'a>,
@@ -1427,6 +1606,13 @@ mod tests {
         }
     }
 
+    fn contract_state_request() -> StateRequestBody {
+        StateRequestBody {
+            contract_ids: Some(vec![Bytes::from("0x0000000000000000000000000000000000000000")]),
+            ..Default::default()
+        }
+    }
+
     const GET_CONTRACT_STATE_RESP: &str = r#"
         {
             "accounts": [
@@ -1512,7 +1698,7 @@ mod tests {
             .expect("create client");
 
         let response = client
-            .get_contract_state(&Default::default())
+            .get_contract_state(&contract_state_request())
             .await
             .expect("get state");
         let accounts = response.accounts;
@@ -1529,6 +1715,89 @@ mod tests {
         );
     }
 
+    #[rstest]
+    #[case::no_ids(None)]
+    #[case::empty_ids(Some(vec![]))]
+    #[tokio::test]
+    async fn test_get_contract_state_requires_explicit_ids(
+        #[case] contract_ids: Option<Vec<Bytes>>,
+    ) {
+        let mut server = Server::new_async().await;
+        let mocked_server = server
+            .mock("POST", "/v1/contract_state")
+            .expect(1)
+            .with_body(GET_CONTRACT_STATE_RESP)
+            .create_async()
+            .await;
+        let request = StateRequestBody { contract_ids, ..Default::default() };
+
+        let client = HttpRPCClient::new(server.url().as_str(), HttpRPCClientOptions::default())
+            .expect("create client");
+        let result = client
+            .get_contract_state(&request)
+            .await;
+        assert!(
+            matches!(result, Err(RPCError::FormatRequest(msg)) if msg == "no contract ids specified")
+        );
+
+        let client = HttpRPCClient::new(
+            server.url().as_str(),
+            HttpRPCClientOptions::default().with_require_explicit_ids(false),
+        )
+        .expect("create client");
+        let response = client
+            .get_contract_state(&request)
+            .await
+            .expect("get state");
+
+        mocked_server.assert();
+        assert_eq!(response.accounts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_contract_states_batch() {
+        let mut server = Server::new_async().await;
+        let mut mocks = Vec::new();
+        for (protocol_system, total) in [("system_a", 1), ("system_b", 2), ("system_c", 3)] {
+            mocks.push(
+                server
+                    .mock("POST", "/v1/contract_state")
+                    .match_body(mockito::Matcher::PartialJson(
+                        serde_json::json!({ "protocol_system": protocol_system }),
+                    ))
+                    .expect(1)
+                    .with_body(
+                        GET_CONTRACT_STATE_RESP
+                            .replace(r#""total": 10"#, &format!(r#""total": {total}"#)),
+                    )
+                    .create_async()
+                    .await,
+            );
+        }
+        let client = HttpRPCClient::new(server.url().as_str(), HttpRPCClientOptions::default())
+            .expect("create client");
+        let requests =
+            ["system_a", "system_b", "system_c"].map(|protocol_system| StateRequestBody {
+                protocol_system: protocol_system.to_string(),
+                contract_ids: Some(vec![Bytes::from("0x01")]),
+                ..Default::default()
+            });
+
+        let responses = client
+            .get_contract_states_batch(&requests, 2)
+            .await
+            .expect("get states");
+
+        for mock in mocks {
+            mock.assert();
+        }
+        let totals: Vec<_> = responses
+            .iter()
+            .map(|r| r.pagination.total)
+            .collect();
+        assert_eq!(totals, vec![1, 2, 3]);
+    }
+
     #[tokio::test]
     async fn test_get_protocol_components() {
         let mut server = Server::new_async().await;
@@ -2034,6 +2303,45 @@ mod tests {
         assert!(matches!(result, Err(RPCError::RateLimited(None))));
     }
 
+    #[tokio::test]
+    async fn test_error_for_response_other_status() {
+        let test_cases = vec![(404, "Not Found"), (500, "<html>Internal Server Error</html>")];
+
+        for (status_code, expected_body) in test_cases {
+            let mut server = Server::new_async().await;
+            let mock = server
+                .mock("GET", "/test")
+                .with_status(status_code)
+                .with_body(expected_body)
+                .create_async()
+                .await;
+
+            let client = reqwest::Client::new();
+            let response = client
+                .get(format!("{}/test", server.url()))
+                .send()
+                .await
+                .unwrap();
+
+            let http_client =
+                HttpRPCClient::new(server.url().as_str(), HttpRPCClientOptions::default())
+                    .unwrap()
+                    .with_test_backoff_policy();
+            let result = http_client
+                .error_for_response(response)
+                .await;
+
+            mock.assert();
+            match result {
+                Err(RPCError::HttpStatus { status, body }) => {
+                    assert_eq!(status.as_u16(), status_code as u16);
+                    assert_eq!(body, expected_body);
+                }
+                other => panic!("Expected HttpStatus error, got {other:?}"),
+            }
+        }
+    }
+
     #[tokio::test]
     async fn test_error_for_response_server_errors() {
         let test_cases =
@@ -2316,6 +2624,232 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_custom_headers() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/test")
+            .match_header("x-api-key", "secret")
+            .with_status(200)
+            .create_async()
+            .await;
+
+        let http_client = HttpRPCClient::new(
+            server.url().as_str(),
+            HttpRPCClientOptions::new().with_header("x-api-key", "secret"),
+        )
+        .unwrap()
+        .with_test_backoff_policy();
+        let uri = format!("{}/test", server.url());
+
+        let result = http_client
+            .make_post_request(&serde_json::json!({}), &uri)
+            .await;
+
+        mock.assert();
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_credentials_redacted_in_debug_output() {
+        let http_client = HttpRPCClient::new(
+            "http://localhost:8080",
+            HttpRPCClientOptions::new()
+                .with_auth_key(Some("Bearer auth-secret".to_string()))
+                .with_header("x-api-key", "header-secret"),
+        )
+        .unwrap();
+
+        let debug_output = format!("{http_client:?}");
+
+        assert!(!debug_output.contains("auth-secret"));
+        assert!(!debug_output.contains("header-secret"));
+    }
+
+    #[test]
+    fn test_invalid_custom_header() {
+        let result = HttpRPCClient::new(
+            "http://localhost:8080",
+            HttpRPCClientOptions::new().with_header("invalid header", "value"),
+        );
+
+        assert!(matches!(result, Err(RPCError::FormatRequest(_))));
+    }
+
+    #[rstest]
+    #[case::ready(r#"{"status":"Ready"}"#, Health::Ready)]
+    #[case::not_ready(
+        r#"{"status":"NotReady","message":"No db connection"}"#,
+        Health::NotReady("No db connection".to_string())
+    )]
+    #[tokio::test]
+    async fn test_get_health(#[case] body: &str, #[case] expected: Health) {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("GET", "/v1/health")
+            .with_status(200)
+            .with_body(body)
+            .create_async()
+            .await;
+        let client = HttpRPCClient::new(server.url().as_str(), HttpRPCClientOptions::default())
+            .expect("create client");
+
+        let health = client
+            .get_health()
+            .await
+            .expect("get health");
+
+        mock.assert();
+        assert_eq!(health, expected);
+    }
+
+    #[test]
+    fn test_endpoint() {
+        let client =
+            |url: &str, options: HttpRPCClientOptions| HttpRPCClient::new(url, options).unwrap();
+
+        assert_eq!(
+            client("http://localhost:4242", HttpRPCClientOptions::new()).endpoint("tokens"),
+            "http://localhost:4242/v1/tokens"
+        );
+        assert_eq!(
+            client("http://localhost:4242/tycho/", HttpRPCClientOptions::new()).endpoint("tokens"),
+            "http://localhost:4242/tycho/v1/tokens"
+        );
+        assert_eq!(
+            client(
+                "http://localhost:4242",
+                HttpRPCClientOptions::new().with_server_version("/api/v2/")
+            )
+            .endpoint("tokens"),
+            "http://localhost:4242/api/v2/tokens"
+        );
+        assert_eq!(
+            client("http://localhost:4242", HttpRPCClientOptions::new().with_server_version(""))
+                .endpoint("tokens"),
+            "http://localhost:4242/tokens"
+        );
+    }
+
+    #[test]
+    fn test_options_default_timeout() {
+        assert_eq!(HttpRPCClientOptions::default().timeout, Some(Duration::from_secs(30)));
+        assert_eq!(
+            HttpRPCClientOptions::new()
+                .with_timeout(None)
+                .timeout,
+            None
+        );
+    }
+
+    #[rstest]
+    #[case::retries_exhausted(1, false)]
+    #[case::retries_sufficient(2, true)]
+    #[tokio::test]
+    async fn test_make_post_request_max_retries(
+        #[case] max_retries: u32,
+        #[case] expect_success: bool,
+    ) {
+        let mut server = Server::new_async().await;
+        let error_mock = server
+            .mock("POST", "/test")
+            .with_status(503)
+            .with_body("Service Unavailable")
+            .expect(2)
+            .create_async()
+            .await;
+        let success_mock = server
+            .mock("POST", "/test")
+            .with_status(200)
+            .expect(usize::from(expect_success))
+            .create_async()
+            .await;
+
+        let http_client = HttpRPCClient::new(
+            server.url().as_str(),
+            HttpRPCClientOptions::new().with_max_retries(Some(max_retries)),
+        )
+        .unwrap()
+        .with_test_backoff_policy();
+        let request_body = serde_json::json!({"test": "data"});
+        let uri = format!("{}/test", server.url());
+
+        let result = http_client
+            .make_post_request(&request_body, &uri)
+            .await;
+
+        error_mock.assert();
+        success_mock.assert();
+        if expect_success {
+            assert!(result.is_ok());
+        } else {
+            assert!(matches!(result, Err(RPCError::ServerUnreachable(_))));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_make_post_request_retry_on_internal_server_error() {
+        let mut server = Server::new_async().await;
+        let error_mock = server
+            .mock("POST", "/test")
+            .with_status(500)
+            .expect(1)
+            .create_async()
+            .await;
+        let success_mock = server
+            .mock("POST", "/test")
+            .with_status(200)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let http_client =
+            HttpRPCClient::new(server.url().as_str(), HttpRPCClientOptions::default())
+                .unwrap()
+                .with_test_backoff_policy();
+        let result = http_client
+            .make_post_request(&serde_json::json!({}), &format!("{}/test", server.url()))
+            .await;
+
+        error_mock.assert();
+        success_mock.assert();
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_make_post_request_timeout() {
+        // A server that accepts connections but never responds
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+
+        let http_client = HttpRPCClient::new(
+            &format!("http://{addr}"),
+            HttpRPCClientOptions::new().with_timeout(Some(Duration::from_millis(10))),
+        )
+        .unwrap()
+        .with_test_backoff_policy();
+        let request_body = serde_json::json!({"test": "data"});
+        let uri = format!("http://{addr}/test");
+
+        let result = tokio::time::timeout(
+            Duration::from_secs(1),
+            http_client.make_post_request(&request_body, &uri),
+        )
+        .await
+        .expect("request was not aborted by the client timeout");
+
+        assert!(matches!(result, Err(RPCError::Timeout(_))));
+        server.abort();
+    }
+
     #[tokio::test]
     async fn test_make_post_request_respect_retry_after_header() {
         let mut server = Server::new_async().await;
@@ -2383,10 +2917,14 @@ mod tests {
             .await;
 
         mock.assert();
-        assert!(result.is_ok()); // 400 doesn't trigger retry logic, just returns the response
-
-        let response = result.unwrap();
-        assert_eq!(response.status(), 400);
+        // 400 is not retried and surfaced with its status and body
+        match result {
+            Err(RPCError::HttpStatus { status, body }) => {
+                assert_eq!(status, StatusCode::BAD_REQUEST);
+                assert_eq!(body, "Bad Request");
+            }
+            other => panic!("Expected HttpStatus error, got {other:?}"),
+        }
     }
 
     #[tokio::test]
@@ -2767,7 +3305,54 @@ mod tests {
         .expect("create client");
 
         let response = client
-            .get_contract_state(&Default::default())
+            .get_contract_state(&contract_state_request())
+            .await
+            .expect("get state");
+        let accounts = response.accounts;
+
+        mocked_server.assert();
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].native_balance, Bytes::from(500u16.to_be_bytes()));
+    }
+
+    #[rstest]
+    #[case::gzip("gzip")]
+    #[case::deflate("deflate")]
+    #[tokio::test]
+    async fn test_compression_flate(#[case] encoding: &str) {
+        use std::io::Write;
+
+        let mut server = Server::new_async().await;
+        let server_resp = GET_CONTRACT_STATE_RESP.as_bytes();
+        let compressed_body = match encoding {
+            "gzip" => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(server_resp).unwrap();
+                encoder.finish().unwrap()
+            }
+            _ => {
+                let mut encoder =
+                    flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(server_resp).unwrap();
+                encoder.finish().unwrap()
+            }
+        };
+
+        let mocked_server = server
+            .mock("POST", "/v1/contract_state")
+            .expect(1)
+            .match_header("Accept-Encoding", mockito::Matcher::Regex(encoding.to_string()))
+            .with_header("Content-Encoding", encoding)
+            .with_body(compressed_body)
+            .create_async()
+            .await;
+
+        let client = HttpRPCClient::new(server.url().as_str(), HttpRPCClientOptions::new())
+            .expect("create client");
+
+        let response = client
+            .get_contract_state(&contract_state_request())
             .await
             .expect("get state");
         let accounts = response.accounts;
@@ -2801,7 +3386,7 @@ mod tests {
         .expect("create client");
 
         let response = client
-            .get_contract_state(&Default::default())
+            .get_contract_state(&contract_state_request())
             .await
             .expect("get state");
         let accounts = response.accounts;
@@ -50,6 +50,21 @@ pub struct ConstantRetryConfiguration {
     cooldown: Duration,
 }
 
+/// Websocket reconnect backoff: the cooldown doubles after each failed attempt, up to
+/// `max_cooldown`.
+#[derive(Clone, Debug)]
+pub struct ExponentialRetryConfiguration {
+    max_attempts: u64,
+    cooldown: Duration,
+    max_cooldown: Duration,
+}
+
+impl ExponentialRetryConfiguration {
+    pub fn new(max_attempts: u64, cooldown: Duration, max_cooldown: Duration) -> Self {
+        Self { max_attempts, cooldown, max_cooldown }
+    }
+}
+
 pub struct TychoStreamBuilder {
     tycho_url: String,
     chain: Chain,
@@ -60,6 +75,7 @@ pub struct TychoStreamBuilder {
     max_missed_blocks: u64,
     state_sync_retry_config: RetryConfiguration,
     websockets_retry_config: RetryConfiguration,
+    websockets_backoff_config: Option<ExponentialRetryConfiguration>,
     no_state: bool,
     auth_key: Option<String>,
     no_tls: bool,
@@ -88,6 +104,7 @@ impl TychoStreamBuilder {
                 128,
                 Duration::from_secs(max(block_time / 6, 1)),
             ),
+            websockets_backoff_config: None,
             no_state: false,
             auth_key: None,
             no_tls: true,
@@ -145,6 +162,17 @@ impl TychoStreamBuilder {
         self
     }
 
+    /// Reconnects the websocket with exponential backoff instead of the constant cooldown of
+    /// `websockets_retry_config`.
+    pub fn websockets_backoff_config(
+        mut self,
+        backoff_config: &ExponentialRetryConfiguration,
+    ) -> Self {
+        self.websockets_backoff_config = Some(backoff_config.clone());
+        self.warn_on_potential_timing_issues();
+        self
+    }
+
     pub fn state_synchronizer_retry_config(mut self, retry_config: &RetryConfiguration) -> Self {
         self.state_sync_retry_config = retry_config.clone();
         self.warn_on_potential_timing_issues();
@@ -154,8 +182,12 @@ impl TychoStreamBuilder {
     fn warn_on_potential_timing_issues(&self) {
         let (RetryConfiguration::Constant(state_config), RetryConfiguration::Constant(ws_config)) =
             (&self.state_sync_retry_config, &self.websockets_retry_config);
+        let ws_cooldown = self
+            .websockets_backoff_config
+            .as_ref()
+            .map_or(ws_config.cooldown, |config| config.cooldown);
 
-        if ws_config.cooldown >= state_config.cooldown {
+        if ws_cooldown >= state_config.cooldown {
             warn!(
                 "Websocket cooldown should be < than state syncronizer cooldown \
                 to avoid spending retries due to disconnected websocket."
@@ -236,8 +268,15 @@ impl TychoStreamBuilder {
         };
 
         // Initialize the WebSocket client
-        let ws_client = match &self.websockets_retry_config {
-            RetryConfiguration::Constant(config) => WsDeltasClient::new_with_reconnects(
+        let ws_client = match (&self.websockets_backoff_config, &self.websockets_retry_config) {
+            (Some(config), _) => WsDeltasClient::new_with_backoff(
+                &tycho_ws_url,
+                auth_key.as_deref(),
+                config.max_attempts,
+                config.cooldown,
+                config.max_cooldown,
+            ),
+            (None, RetryConfiguration::Constant(config)) => WsDeltasClient::new_with_reconnects(
                 &tycho_ws_url,
                 auth_key.as_deref(),
                 config.max_attempts,
@@ -370,6 +409,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_stream_builder_websockets_backoff_config() {
+        let backoff =
+            ExponentialRetryConfiguration::new(5, Duration::from_secs(1), Duration::from_secs(30));
+
+        let builder = TychoStreamBuilder::new("localhost:4242", Chain::Ethereum)
+            .websockets_backoff_config(&backoff);
+
+        let config = builder
+            .websockets_backoff_config
+            .expect("backoff config not set");
+        assert_eq!(config.max_attempts, 5);
+        assert_eq!(config.cooldown, Duration::from_secs(1));
+        assert_eq!(config.max_cooldown, Duration::from_secs(30));
+    }
+
     #[test]
     fn test_stream_builder_retry_configs() {
         let mut builder = TychoStreamBuilder::new("localhost:4242", Chain::Ethereum);
@@ -9,6 +9,7 @@ use std::{
     collections::{BTreeMap, HashMap, HashSet},
     fmt,
     hash::{Hash, Hasher},
+    str::FromStr,
 };
 
 use chrono::{NaiveDateTime, Utc};
@@ -20,6 +21,7 @@ use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
+    hex_bytes::ParseBytesError,
     models::{
         self, blockchain::BlockAggregatedChanges, Address, Balance, Code, ComponentId, StoreKey,
         StoreVal,
@@ -910,6 +912,85 @@ impl StateRequestBody {
     }
 }
 
+/// Query parameters for retrieving contract states with a GET request.
+///
+/// Flattened counterpart of [`StateRequestBody`], since nested values can't be passed as query
+/// parameters. If neither a block number nor a block hash is given, the state at `timestamp` is
+/// returned, defaulting to the latest state.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, IntoParams)]
+#[into_params(parameter_in = Query)]
+#[serde(deny_unknown_fields)]
+pub struct StateRequestQuery {
+    /// Comma separated contract addresses to filter by
+    pub contract_ids: Option<String>,
+    /// Does not filter response, only required to correctly apply unconfirmed state
+    /// from ReorgBuffers
+    #[serde(default)]
+    pub protocol_system: String,
+    #[serde(default)]
+    pub chain: Chain,
+    /// Number of the block to retrieve the state at
+    pub block_number: Option<i64>,
+    /// Hash of the block to retrieve the state at
+    pub block_hash: Option<String>,
+    /// Timestamp to retrieve the state at, ignored if a block is given
+    pub timestamp: Option<NaiveDateTime>,
+    /// What page to retrieve
+    #[param(default = 0)]
+    pub page: Option<i64>,
+    /// How many results to return per page
+    #[param(default = 100)]
+    pub page_size: Option<i64>,
+}
+
+impl TryFrom<StateRequestQuery> for StateRequestBody {
+    type Error = ParseBytesError;
+
+    fn try_from(query: StateRequestQuery) -> Result<Self, Self::Error> {
+        let default_pagination = PaginationParams::default();
+        let contract_ids = query
+            .contract_ids
+            .map(|ids| {
+                ids.split(',')
+                    .map(|id| Bytes::from_str(id.trim()))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .transpose()?;
+        let version = if query.block_number.is_some() || query.block_hash.is_some() {
+            let hash = query
+                .block_hash
+                .as_deref()
+                .map(Bytes::from_str)
+                .transpose()?;
+            VersionParam::new(
+                None,
+                Some(BlockParam { hash, chain: None, number: query.block_number }),
+            )
+        } else {
+            VersionParam::new(
+                query
+                    .timestamp
+                    .or_else(|| Some(Utc::now().naive_utc())),
+                None,
+            )
+        };
+        Ok(Self::new(
+            contract_ids,
+            query.protocol_system,
+            version,
+            query.chain,
+            PaginationParams::new(
+                query
+                    .page
+                    .unwrap_or(default_pagination.page),
+                query
+                    .page_size
+                    .unwrap_or(default_pagination.page_size),
+            ),
+        ))
+    }
+}
+
 /// Response from Tycho server for a contract state request.
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, ToSchema, DeepSizeOf)]
 pub struct StateRequestResponse {
@@ -1580,7 +1661,7 @@ pub struct ProtocolComponentId {
     pub id: String,
 }
 
-#[derive(Debug, Serialize, ToSchema)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
 #[serde(tag = "status", content = "message")]
 #[schema(example = json!({"status": "NotReady", "message": "No db connection"}))]
 pub enum Health {
@@ -1589,6 +1670,22 @@ pub enum Health {
     NotReady(String),
 }
 
+/// Error body returned by the RPC endpoints.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[schema(example = json!({"code": "parse_error", "message": "Invalid block hash"}))]
+pub struct RpcErrorResponse {
+    /// Machine readable error code, e.g. `not_found` or `connection_error`
+    pub code: String,
+    /// Human readable description of the error
+    pub message: String,
+}
+
+impl RpcErrorResponse {
+    pub fn new(code: &str, message: String) -> Self {
+        Self { code: code.to_string(), message }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, PartialEq, ToSchema, Eq, Hash, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct ProtocolSystemsRequestBody {
@@ -2331,6 +2428,43 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_state_request_from_query() {
+        let query = StateRequestQuery {
+            contract_ids: Some("0xb4eccE46b8D4e4abFd03C9B806276A6735C9c092, 0xbabe".to_string()),
+            protocol_system: "uniswap_v2".to_string(),
+            block_number: Some(213),
+            ..Default::default()
+        };
+
+        let result = StateRequestBody::try_from(query).unwrap();
+
+        let expected = StateRequestBody {
+            contract_ids: Some(vec![
+                Bytes::from_str("b4eccE46b8D4e4abFd03C9B806276A6735C9c092").unwrap(),
+                Bytes::from_str("babe").unwrap(),
+            ]),
+            protocol_system: "uniswap_v2".to_string(),
+            version: VersionParam {
+                timestamp: None,
+                block: Some(BlockParam { hash: None, chain: None, number: Some(213) }),
+            },
+            chain: Chain::Ethereum,
+            pagination: PaginationParams { page: 0, page_size: 100 },
+        };
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_state_request_from_query_invalid_contract_id() {
+        let query = StateRequestQuery {
+            contract_ids: Some("0xbabe,notanaddress".to_string()),
+            ..Default::default()
+        };
+
+        assert!(StateRequestBody::try_from(query).is_err());
+    }
+
     #[test]
     fn test_parse_state_request_no_contract_specified() {
         let json_str = r#"
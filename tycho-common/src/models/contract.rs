@@ -99,7 +99,13 @@ pub struct AccountDelta {
     pub address: Address,
     pub slots: ContractStoreDeltas,
     pub balance: Option<Balance>,
+    /// The account nonce, if known. Only populated by snapshot extraction.
+    #[serde(default)]
+    pub nonce: Option<u64>,
     code: Option<Code>,
+    /// The keccak256 hash of `code`. Only set if the delta carries non-empty code.
+    #[serde(default)]
+    code_hash: Option<CodeHash>,
     change: ChangeType,
 }
 
@@ -124,7 +130,13 @@ impl AccountDelta {
         if code.is_none() && matches!(change, ChangeType::Creation) {
             warn!(?address, "Instantiated AccountDelta without code marked as creation!")
         }
-        Self { chain, address, slots, balance, code, change }
+        let code_hash = Self::hash_code(code.as_ref());
+        Self { chain, address, slots, balance, nonce: None, code, code_hash, change }
+    }
+
+    fn hash_code(code: Option<&Code>) -> Option<CodeHash> {
+        code.filter(|code| !code.is_empty())
+            .map(|code| keccak256(code).into())
     }
 
     pub fn contract_id(&self) -> ContractId {
@@ -250,7 +262,13 @@ impl AccountDelta {
         if let Some(balance) = other.balance {
             self.balance = Some(balance)
         }
-        self.code = other.code.or(self.code.take());
+        if let Some(nonce) = other.nonce {
+            self.nonce = Some(nonce)
+        }
+        if other.code.is_some() {
+            self.code = other.code;
+            self.code_hash = other.code_hash;
+        }
 
         if self.code.is_none() && matches!(self.change, ChangeType::Creation) {
             warn!(address=?self.address, "AccountDelta without code marked as creation after merge!")
@@ -275,7 +293,12 @@ impl AccountDelta {
         &self.code
     }
 
+    pub fn code_hash(&self) -> &Option<CodeHash> {
+        &self.code_hash
+    }
+
     pub fn set_code(&mut self, code: Bytes) {
+        self.code_hash = Self::hash_code(Some(&code));
         self.code = Some(code)
     }
 }
@@ -500,6 +523,22 @@ impl ContractStorageChange {
     pub fn initial(value: impl Into<Bytes>) -> Self {
         Self { value: value.into(), previous: Bytes::default() }
     }
+
+    /// Returns true if the slot was cleared, i.e. its new value is empty or zero.
+    pub fn is_deletion(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    /// Returns the new value of the slot, or `None` if the slot was cleared.
+    ///
+    /// This matches how slots are represented in [`AccountDelta`].
+    pub fn updated_value(&self) -> Option<Bytes> {
+        if self.is_deletion() {
+            None
+        } else {
+            Some(self.value.clone())
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Default, Clone, DeepSizeOf)]
@@ -568,15 +607,38 @@ mod test {
     #[test]
     fn test_merge_account_deltas() {
         let mut update_left = update_balance_delta();
-        let update_right = update_slots_delta();
+        let mut update_right = update_slots_delta();
+        update_right.nonce = Some(7);
         let mut exp = update_slots_delta();
         exp.balance = Some(Bytes::from(420u64).lpad(32, 0));
+        exp.nonce = Some(7);
 
         update_left.merge(update_right).unwrap();
 
         assert_eq!(update_left, exp);
     }
 
+    #[test]
+    fn test_account_delta_code_hash() {
+        let mut delta = AccountDelta::new(
+            Chain::Ethereum,
+            Bytes::zero(20),
+            HashMap::new(),
+            None,
+            Some(Bytes::from(vec![0x60, 0x01])),
+            ChangeType::Creation,
+        );
+        let code_hash =
+            Bytes::from_str("0x309c67890bde4c575dc23d2cc3b5c3a3d599e312e980e9b61b5bc8f3cd87c8bb")
+                .unwrap();
+
+        assert_eq!(delta.code_hash(), &Some(code_hash));
+
+        delta.set_code(Bytes::new());
+
+        assert_eq!(delta.code_hash(), &None);
+    }
+
     #[test]
     fn test_merge_account_delta_wrong_address() {
         let mut update_left = update_balance_delta();
@@ -874,4 +936,16 @@ mod test {
 
         assert_eq!(to_merge_on.account_deltas, acc_update);
     }
+
+    #[rstest]
+    #[case::value(ContractStorageChange::new(Bytes::from(1u64).lpad(32, 0), Bytes::zero(32)), Some(Bytes::from(1u64).lpad(32, 0)))]
+    #[case::zeroed(ContractStorageChange::new(Bytes::zero(32), Bytes::from(1u64).lpad(32, 0)), None)]
+    #[case::empty(ContractStorageChange::new(Bytes::new(), Bytes::from(1u64).lpad(32, 0)), None)]
+    fn test_contract_storage_change_updated_value(
+        #[case] change: ContractStorageChange,
+        #[case] expected: Option<Bytes>,
+    ) {
+        assert_eq!(change.is_deletion(), expected.is_none());
+        assert_eq!(change.updated_value(), expected);
+    }
 }
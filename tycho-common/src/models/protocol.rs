@@ -66,6 +66,25 @@ impl ProtocolComponent {
             created_at,
         }
     }
+
+    /// Returns the static attribute `name` decoded as a big-endian unsigned integer, e.g. for
+    /// attributes like `fee` or `tick_spacing`.
+    ///
+    /// Static attributes carry no type information, so this is a best-effort view: it returns
+    /// `None` if the attribute is missing or its value does not fit into a `u64`.
+    pub fn static_attribute_u64(&self, name: &str) -> Option<u64> {
+        let value = self.static_attributes.get(name)?;
+        let significant = value
+            .iter()
+            .position(|byte| *byte != 0)
+            .map_or(&[][..], |start| &value[start..]);
+        if significant.len() > 8 {
+            return None;
+        }
+        let mut buf = [0u8; 8];
+        buf[8 - significant.len()..].copy_from_slice(significant);
+        Some(u64::from_be_bytes(buf))
+    }
 }
 
 impl DeepSizeOf for ProtocolComponent {
@@ -370,6 +389,24 @@ mod test {
         }
     }
 
+    #[rstest]
+    #[case::padded(Some(Bytes::from(3000u64).lpad(32, 0)), Some(3000))]
+    #[case::compact(Some(Bytes::from(vec![0x0b, 0xb8])), Some(3000))]
+    #[case::empty(Some(Bytes::new()), Some(0))]
+    #[case::too_large(Some(Bytes::from(u128::MAX)), None)]
+    #[case::missing(None, None)]
+    fn test_static_attribute_u64(#[case] value: Option<Bytes>, #[case] expected: Option<u64>) {
+        let component = ProtocolComponent {
+            static_attributes: value
+                .into_iter()
+                .map(|value| ("fee".to_string(), value))
+                .collect(),
+            ..Default::default()
+        };
+
+        assert_eq!(component.static_attribute_u64("fee"), expected);
+    }
+
     #[test]
     fn test_merge_protocol_state_updates() {
         let mut state_1 = create_state("State1".to_owned());
@@ -52,6 +52,95 @@ impl fmt::Display for GetAmountOutResult {
     }
 }
 
+/// GetAmountInResult struct represents the input amount required to receive a given amount out of
+/// a trading pair
+///
+/// # Fields
+///
+/// * `amount`: BigUint, the amount of the input token required
+/// * `gas`: BigUint, the gas of the trading pair
+#[derive(Debug)]
+pub struct GetAmountInResult {
+    pub amount: BigUint,
+    pub gas: BigUint,
+    pub new_state: Box<dyn ProtocolSim>,
+}
+
+impl GetAmountInResult {
+    /// Constructs a new GetAmountInResult struct with the given amount and gas
+    pub fn new(amount: BigUint, gas: BigUint, new_state: Box<dyn ProtocolSim>) -> Self {
+        GetAmountInResult { amount, gas, new_state }
+    }
+}
+
+impl fmt::Display for GetAmountInResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "amount = {}, gas = {}", self.amount, self.gas)
+    }
+}
+
+/// The maximum amounts that can be traded between two tokens, see [ProtocolSim::get_limits].
+///
+/// # Fields
+///
+/// * `max_in` - The maximum input amount of the sell token
+/// * `max_out` - The maximum output amount of the buy token
+/// * `hard_in` - Whether `max_in` is a hard protocol constraint. If `false`, it is a soft advisory
+///   limit: larger amounts may succeed but are advised against.
+/// * `hard_out` - Whether `max_out` is a hard protocol constraint, with the same semantics.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limits {
+    pub max_in: BigUint,
+    pub max_out: BigUint,
+    pub hard_in: bool,
+    pub hard_out: bool,
+}
+
+impl Limits {
+    pub fn new(max_in: BigUint, max_out: BigUint, hard_in: bool, hard_out: bool) -> Self {
+        Self { max_in, max_out, hard_in, hard_out }
+    }
+}
+
+/// Describes what changed when applying a delta, see [ProtocolSim::delta_transition_summary].
+///
+/// # Fields
+///
+/// * `spot_price_changed` - Whether the spot price may have moved
+/// * `reserves_changed` - Whether the reserves or liquidity may have moved
+/// * `fee_changed` - Whether the fee may have changed
+/// * `uninitialized` - Whether the state became uninitialized or empty, e.g. all liquidity was
+///   removed
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TransitionSummary {
+    pub spot_price_changed: bool,
+    pub reserves_changed: bool,
+    pub fee_changed: bool,
+    pub uninitialized: bool,
+}
+
+impl TransitionSummary {
+    /// A summary for a transition that did not change any tracked quantity.
+    pub fn unchanged() -> Self {
+        Self::default()
+    }
+
+    /// A summary for a transition that may have changed every tracked quantity.
+    pub fn all_changed() -> Self {
+        Self {
+            spot_price_changed: true,
+            reserves_changed: true,
+            fee_changed: true,
+            uninitialized: false,
+        }
+    }
+
+    /// Returns true if any tracked quantity changed.
+    pub fn has_changes(&self) -> bool {
+        self.spot_price_changed || self.reserves_changed || self.fee_changed || self.uninitialized
+    }
+}
+
 /// Represents a price as a fraction in the token_in -> token_out direction with units
 /// `[token_out/token_in]`.
 ///
@@ -242,7 +331,18 @@ pub trait ProtocolSim: fmt::Debug + Send + Sync + 'static {
     /// where a single fee value cannot represent the protocol's fee structure.
     fn fee(&self) -> f64;
 
-    /// Returns the protocol's current spot buy price for `base` in units of `quote`.
+    /// Returns the fee of the protocol as ratio when trading `token_in` for `token_out`.
+    ///
+    /// Protocols with asymmetric or pair-dependent fees (e.g. hooks or tax tokens) should override
+    /// it, so consumers computing effective prices don't underestimate the cost of a trade. The
+    /// default implementation returns [`ProtocolSim::fee`].
+    #[allow(unused)]
+    fn fee_on_pair(&self, token_in: &Token, token_out: &Token) -> Result<f64, SimulationError> {
+        Ok(self.fee())
+    }
+
+    /// Returns the protocol's current spot buy price for `base` in units of `quote`, including the
+    /// protocol fee.
     ///
     /// The returned price is the amount of `quote` required to buy exactly 1 unit of `base`,
     /// accounting for the protocol fee (i.e. `price = pre_fee_price / (1.0 - fee)`)
@@ -256,6 +356,23 @@ pub trait ProtocolSim: fmt::Debug + Send + Sync + 'static {
     /// If the BTC/USDT is trading at 1000 with a 20% fee, this returns `1000 / (1.0 - 0.20) = 1250`
     fn spot_price(&self, base: &Token, quote: &Token) -> Result<f64, SimulationError>;
 
+    /// Returns the protocol's current spot buy price for `base` in units of `quote`, excluding the
+    /// protocol fee.
+    ///
+    /// This is the gross marginal price, i.e. `spot_price * (1.0 - fee)`. Unlike
+    /// [`ProtocolSim::spot_price`] it does not include the fee.
+    ///
+    /// The default implementation derives it from [`ProtocolSim::spot_price`] and
+    /// [`ProtocolSim::fee`]. Protocols with asymmetric fees, for which `fee` panics, must override
+    /// it.
+    fn spot_price_no_fee(&self, base: &Token, quote: &Token) -> Result<f64, SimulationError> {
+        Ok(self.spot_price(base, quote)? * (1.0 - self.fee()))
+    }
+
+    /// Returns the addresses of all tokens traded by the component, including for pools with more
+    /// than two tokens.
+    fn tokens(&self) -> Vec<Bytes>;
+
     /// Returns the amount out given an amount in and input/output tokens.
     ///
     /// # Arguments
@@ -275,6 +392,96 @@ pub trait ProtocolSim: fmt::Debug + Send + Sync + 'static {
         token_out: &Token,
     ) -> Result<GetAmountOutResult, SimulationError>;
 
+    /// Returns the amount out for each of the given amounts in, e.g. to build a price curve.
+    ///
+    /// Each amount is simulated independently against the current state. The default
+    /// implementation calls [`ProtocolSim::get_amount_out`] for every amount; protocols with
+    /// expensive state setup can override it to share that work across amounts.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing one `GetAmountOutResult` per amount in, in the same order, or the
+    /// first `SimulationError` encountered.
+    fn get_amount_out_batch(
+        &self,
+        amounts_in: &[BigUint],
+        token_in: &Token,
+        token_out: &Token,
+    ) -> Result<Vec<GetAmountOutResult>, SimulationError> {
+        amounts_in
+            .iter()
+            .map(|amount_in| self.get_amount_out(amount_in.clone(), token_in, token_out))
+            .collect()
+    }
+
+    /// Returns the price impact of selling `amount_in` of `token_in` for `token_out`.
+    ///
+    /// The impact is the fractional shortfall of the executed rate (`amount_out / amount_in`,
+    /// adjusted for token decimals) against the rate implied by the current spot price, i.e.
+    /// `1 - exec_rate * spot_price(token_out, token_in)`. Since the spot price includes the fee,
+    /// the result only reflects slippage: `0.01` means the trade executes 1% worse than spot.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(0.0)` if `amount_in` is zero.
+    /// * `Err(SimulationError::InvalidInput)` if `amount_in` exceeds the maximum input amount
+    ///   returned by [`ProtocolSim::get_limits`].
+    /// * Any `SimulationError` returned by [`ProtocolSim::get_amount_out`] or
+    ///   [`ProtocolSim::spot_price`].
+    fn price_impact(
+        &self,
+        amount_in: BigUint,
+        token_in: &Token,
+        token_out: &Token,
+    ) -> Result<f64, SimulationError> {
+        if amount_in == BigUint::ZERO {
+            return Ok(0.0);
+        }
+        let (max_amount_in, _) =
+            self.get_limits(token_in.address.clone(), token_out.address.clone())?;
+        if amount_in > max_amount_in {
+            return Err(SimulationError::InvalidInput(
+                format!("amount in {amount_in} exceeds the sell limit {max_amount_in}"),
+                None,
+            ));
+        }
+        let to_units = |amount: &BigUint, decimals: u32| {
+            amount
+                .to_string()
+                .parse::<f64>()
+                .unwrap_or(f64::INFINITY) /
+                10f64.powi(decimals as i32)
+        };
+        let amount_out = self
+            .get_amount_out(amount_in.clone(), token_in, token_out)?
+            .amount;
+        let exec_rate =
+            to_units(&amount_out, token_out.decimals) / to_units(&amount_in, token_in.decimals);
+        Ok(1.0 - exec_rate * self.spot_price(token_out, token_in)?)
+    }
+
+    /// Returns the amount in required to receive exactly `amount_out` of the output token.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount_out` - The desired amount out of the output token.
+    /// * `token_in` - The input token ERC20 token.
+    /// * `token_out` - The output token ERC20 token.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing a `GetAmountInResult` struct on success or a
+    ///  `SimulationError` on failure, including for protocols that don't support it.
+    #[allow(unused)]
+    fn get_amount_in(
+        &self,
+        amount_out: BigUint,
+        token_in: &Token,
+        token_out: &Token,
+    ) -> Result<GetAmountInResult, SimulationError> {
+        Err(SimulationError::FatalError("get_amount_in not implemented".into()))
+    }
+
     /// Computes the maximum amount that can be traded between two tokens.
     ///
     /// This function calculates the maximum possible trade amount between two tokens,
@@ -298,7 +505,7 @@ pub trait ProtocolSim: fmt::Debug + Send + Sync + 'static {
     ///   - Second element: The maximum output amount (buy_token)
     ///
     /// For `let res = get_limits(...)`, the valid input domain for `get_amount_out` is `[0,
-    /// res.0]`.
+    /// res.0]` and for `get_amount_in` it is `[0, res.1]`.
     ///
     /// * `Err(SimulationError)` - If any unexpected error occurs
     fn get_limits(
@@ -307,6 +514,43 @@ pub trait ProtocolSim: fmt::Debug + Send + Sync + 'static {
         buy_token: Bytes,
     ) -> Result<(BigUint, BigUint), SimulationError>;
 
+    /// Returns the minimum amount of `token_in` that can be traded, e.g. for protocols that reject
+    /// dust trades.
+    ///
+    /// This is the lower bound of the valid input domain for [`ProtocolSim::get_amount_out`],
+    /// complementing the upper bound returned by [`ProtocolSim::get_limits`]. Routers can use it
+    /// to filter out amounts that would revert. The default implementation returns zero.
+    #[allow(unused)]
+    fn min_trade_amount(&self, token_in: &Token) -> Result<BigUint, SimulationError> {
+        Ok(BigUint::ZERO)
+    }
+
+    /// Same as [`ProtocolSim::get_limits`], but also conveys per direction whether each limit is a
+    /// hard protocol constraint or a soft advisory.
+    ///
+    /// The default implementation wraps [`ProtocolSim::get_limits`] and reports both limits as
+    /// soft, since it can't know otherwise. Protocols whose limits are enforced (e.g. by available
+    /// reserves) should override it.
+    fn get_limits_detailed(
+        &self,
+        sell_token: Bytes,
+        buy_token: Bytes,
+    ) -> Result<Limits, SimulationError> {
+        let (max_in, max_out) = self.get_limits(sell_token, buy_token)?;
+        Ok(Limits::new(max_in, max_out, false, false))
+    }
+
+    /// Returns the raw reserves of the component per token address, e.g. to compute depth or TVL.
+    ///
+    /// # Returns
+    ///
+    /// A `Result` containing the reserve amount of each token on success, or a
+    /// `SimulationError` for protocols where reserves aren't meaningful (e.g. RFQ), which is
+    /// the default.
+    fn get_reserves(&self) -> Result<HashMap<Bytes, BigUint>, SimulationError> {
+        Err(SimulationError::FatalError("get_reserves not implemented".into()))
+    }
+
     /// Decodes and applies a protocol state delta to the state
     ///
     /// Will error if the provided delta is missing any required attributes or if any of the
@@ -327,6 +571,29 @@ pub trait ProtocolSim: fmt::Debug + Send + Sync + 'static {
         balances: &Balances,
     ) -> Result<(), TransitionError<String>>;
 
+    /// Same as [`ProtocolSim::delta_transition`], but returns a [TransitionSummary] describing
+    /// which tracked quantities changed. This allows skipping re-pricing pools that were not
+    /// affected by a block.
+    ///
+    /// The default implementation compares the state before and after the transition using
+    /// [`ProtocolSim::eq`], which requires cloning the state. If anything changed, every quantity
+    /// is reported as changed and `uninitialized` is never set. Protocols should override it to
+    /// report changes precisely.
+    fn delta_transition_summary(
+        &mut self,
+        delta: ProtocolStateDelta,
+        tokens: &HashMap<Bytes, Token>,
+        balances: &Balances,
+    ) -> Result<TransitionSummary, TransitionError<String>> {
+        let previous = self.clone_box();
+        self.delta_transition(delta, tokens, balances)?;
+        if ProtocolSim::eq(self, previous.as_ref()) {
+            Ok(TransitionSummary::unchanged())
+        } else {
+            Ok(TransitionSummary::all_changed())
+        }
+    }
+
     /// Calculates the swap volume required to achieve the provided goal when trading against this
     /// pool.
     ///
@@ -373,6 +640,49 @@ pub trait ProtocolSim: fmt::Debug + Send + Sync + 'static {
     fn as_indicatively_priced(&self) -> Result<&dyn IndicativelyPriced, SimulationError> {
         Err(SimulationError::FatalError("Pool State does not implement IndicativelyPriced".into()))
     }
+
+    /// Serializes the state so it can be persisted and later restored.
+    ///
+    /// Serialization is opt-in: states that support it implement [SerializableProtocolSim] and
+    /// override this method to return `SerializableProtocolSim::to_bytes`. The default returns an
+    /// error.
+    fn serialize_state(&self) -> Result<Vec<u8>, SimulationError> {
+        Err(SimulationError::FatalError("serialize_state not implemented".into()))
+    }
+}
+
+/// Protocol states that can be persisted and restored as `Box<dyn ProtocolSim>`.
+///
+/// Implementations must be annotated with `#[typetag::serde]` and implement `Serialize` and
+/// `Deserialize`.
+#[typetag::serde(tag = "protocol", content = "state")]
+pub trait SerializableProtocolSim: ProtocolSim {}
+
+impl dyn SerializableProtocolSim {
+    /// The name the state is tagged with when serialized, i.e. the implementing type's name.
+    pub fn protocol_type_name(&self) -> &'static str {
+        self.typetag_name()
+    }
+
+    /// Serializes the state, tagged with its protocol type name, so it can later be restored with
+    /// `from_bytes`.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, SimulationError> {
+        serde_json::to_vec(self).map_err(|e| {
+            SimulationError::FatalError(format!("Failed to serialize protocol state: {e}"))
+        })
+    }
+
+    /// Restores a state serialized with `to_bytes` or `ProtocolSim::serialize_state`.
+    ///
+    /// The concrete type is looked up by the protocol type name stored alongside the state, so it
+    /// must be registered via `#[typetag::serde]` in the running binary.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Box<dyn ProtocolSim>, SimulationError> {
+        let state: Box<dyn SerializableProtocolSim> =
+            serde_json::from_slice(bytes).map_err(|e| {
+                SimulationError::FatalError(format!("Failed to deserialize protocol state: {e}"))
+            })?;
+        Ok(state)
+    }
 }
 
 impl Clone for Box<dyn ProtocolSim> {
@@ -380,3 +690,144 @@ impl Clone for Box<dyn ProtocolSim> {
         self.clone_box()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use serde::{Deserialize, Serialize};
+
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct ConstantPriceState {
+        price: f64,
+        reserve: u64,
+    }
+
+    #[typetag::serde]
+    impl SerializableProtocolSim for ConstantPriceState {}
+
+    impl ProtocolSim for ConstantPriceState {
+        fn fee(&self) -> f64 {
+            0.0
+        }
+
+        fn spot_price(&self, _base: &Token, _quote: &Token) -> Result<f64, SimulationError> {
+            Ok(self.price)
+        }
+
+        fn tokens(&self) -> Vec<Bytes> {
+            vec![Bytes::from("0x01"), Bytes::from("0x02")]
+        }
+
+        fn get_amount_out(
+            &self,
+            amount_in: BigUint,
+            _token_in: &Token,
+            _token_out: &Token,
+        ) -> Result<GetAmountOutResult, SimulationError> {
+            Ok(GetAmountOutResult::new(amount_in, BigUint::ZERO, self.clone_box()))
+        }
+
+        fn get_limits(
+            &self,
+            _sell_token: Bytes,
+            _buy_token: Bytes,
+        ) -> Result<(BigUint, BigUint), SimulationError> {
+            Ok((BigUint::from(self.reserve), BigUint::from(self.reserve)))
+        }
+
+        fn delta_transition(
+            &mut self,
+            delta: ProtocolStateDelta,
+            _tokens: &HashMap<Bytes, Token>,
+            _balances: &Balances,
+        ) -> Result<(), TransitionError<String>> {
+            if let Some(reserve) = delta.updated_attributes.get("reserve") {
+                self.reserve = u64::from_be_bytes(
+                    reserve
+                        .as_ref()
+                        .try_into()
+                        .map_err(|_| TransitionError::DecodeError("reserve".to_owned()))?,
+                );
+            }
+            Ok(())
+        }
+
+        fn clone_box(&self) -> Box<dyn ProtocolSim> {
+            Box::new(self.clone())
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        fn eq(&self, other: &dyn ProtocolSim) -> bool {
+            other
+                .as_any()
+                .downcast_ref::<Self>()
+                .is_some_and(|other| self == other)
+        }
+
+        fn serialize_state(&self) -> Result<Vec<u8>, SimulationError> {
+            (self as &dyn SerializableProtocolSim).to_bytes()
+        }
+    }
+
+    #[test]
+    fn test_serialize_state_roundtrip() {
+        let state: Box<dyn ProtocolSim> =
+            Box::new(ConstantPriceState { price: 1.5, reserve: 1_000_000 });
+
+        let bytes = state.serialize_state().unwrap();
+        let restored = <dyn SerializableProtocolSim>::from_bytes(&bytes).unwrap();
+
+        assert!(restored.eq(state.as_ref()));
+    }
+
+    #[test]
+    fn test_protocol_type_name() {
+        let state = ConstantPriceState { price: 1.5, reserve: 1_000_000 };
+
+        let name = (&state as &dyn SerializableProtocolSim).protocol_type_name();
+
+        assert_eq!(name, "ConstantPriceState");
+    }
+
+    #[test]
+    fn test_get_limits_detailed_defaults_to_soft() {
+        let state = ConstantPriceState { price: 1.5, reserve: 1_000_000 };
+
+        let limits = state
+            .get_limits_detailed(Bytes::from("0x01"), Bytes::from("0x02"))
+            .unwrap();
+
+        assert_eq!(
+            limits,
+            Limits::new(BigUint::from(1_000_000u64), BigUint::from(1_000_000u64), false, false)
+        );
+    }
+
+    #[rstest]
+    #[case::unchanged(1_000_000, TransitionSummary::unchanged())]
+    #[case::changed(2_000_000, TransitionSummary::all_changed())]
+    fn test_delta_transition_summary(#[case] reserve: u64, #[case] expected: TransitionSummary) {
+        let mut state = ConstantPriceState { price: 1.5, reserve: 1_000_000 };
+        let delta = ProtocolStateDelta {
+            component_id: "pool".to_owned(),
+            updated_attributes: HashMap::from([("reserve".to_owned(), Bytes::from(reserve))]),
+            deleted_attributes: Default::default(),
+        };
+
+        let summary = state
+            .delta_transition_summary(delta, &HashMap::new(), &Balances::default())
+            .unwrap();
+
+        assert_eq!(summary, expected);
+        assert_eq!(state.reserve, reserve);
+    }
+}
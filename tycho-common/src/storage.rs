@@ -781,6 +781,7 @@ pub trait ContractStateGateway {
     ) -> Result<HashMap<Address, HashMap<Address, AccountBalance>>, StorageError>;
 }
 
+#[async_trait]
 pub trait Gateway:
     ChainGateway
     + ContractStateGateway
@@ -791,4 +792,9 @@ pub trait Gateway:
     + Send
     + Sync
 {
+    /// Checks that the underlying storage is reachable.
+    ///
+    /// # Returns
+    /// - Empty ok result if a connection could be acquired and answered a trivial query.
+    async fn check_connection(&self) -> Result<(), StorageError>;
 }
@@ -1,3 +1,5 @@
+use crate::RPCError;
+
 /// Configuration for RPC provider retry behavior
 #[derive(Clone, Debug)]
 pub struct RPCRetryConfig {
@@ -21,6 +23,9 @@ impl Default for RPCRetryConfig {
     }
 }
 
+/// Minimum `max_batch_size`: account code, balance and nonce are fetched in the same batch.
+pub(crate) const MIN_MAX_BATCH_SIZE: usize = 3;
+
 /// Configuration for RPC request batching behavior.
 #[derive(Clone, Debug, Default)]
 pub enum RPCBatchingConfig {
@@ -46,6 +51,25 @@ impl RPCBatchingConfig {
         Self::Enabled { max_batch_size: 50, storage_slot_max_batch_size_override: Some(1000) }
     }
 
+    /// Creates an enabled batching config, validating that `max_batch_size` fits the code,
+    /// balance and nonce requests of an account and that the storage override is non-zero.
+    pub fn enabled(
+        max_batch_size: usize,
+        storage_slot_max_batch_size_override: Option<usize>,
+    ) -> Result<Self, RPCError> {
+        if max_batch_size < MIN_MAX_BATCH_SIZE {
+            return Err(RPCError::SetupError(format!(
+                "BatchingConfig max_batch_size must be at least {MIN_MAX_BATCH_SIZE}"
+            )));
+        }
+        if storage_slot_max_batch_size_override == Some(0) {
+            return Err(RPCError::SetupError(
+                "BatchingConfig storage_slot_max_batch_size_override must be non-zero".to_string(),
+            ));
+        }
+        Ok(Self::Enabled { max_batch_size, storage_slot_max_batch_size_override })
+    }
+
     /// Returns the max batch size if batching is enabled, `None` otherwise.
     pub fn max_batch_size(&self) -> Option<usize> {
         match self {
@@ -84,6 +108,25 @@ mod tests {
         assert_eq!(config.storage_slot_max_batch_size(), Some(50));
     }
 
+    #[rstest]
+    #[case::zero_max_batch_size(0, None)]
+    #[case::max_batch_size_below_minimum(2, None)]
+    #[case::zero_storage_override(50, Some(0))]
+    fn test_enabled_batching_config_rejects_invalid_sizes(
+        #[case] max_batch_size: usize,
+        #[case] storage_override: Option<usize>,
+    ) {
+        let result = RPCBatchingConfig::enabled(max_batch_size, storage_override);
+        assert!(matches!(result, Err(RPCError::SetupError(_))));
+    }
+
+    #[test]
+    fn test_enabled_batching_config() {
+        let config = RPCBatchingConfig::enabled(100, Some(10)).expect("valid config");
+        assert_eq!(config.max_batch_size(), Some(100));
+        assert_eq!(config.storage_slot_max_batch_size(), Some(10));
+    }
+
     #[test]
     fn test_disabled_batching_config() {
         assert_eq!(RPCBatchingConfig::Disabled.max_batch_size(), None);
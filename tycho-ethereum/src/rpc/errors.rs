@@ -1,8 +1,10 @@
-use std::fmt::Display;
+use std::{fmt::Display, time::Duration};
 
 use alloy::transports::{RpcError as AlloyRpcError, TransportErrorKind};
 use thiserror::Error;
 
+use crate::rpc::retry::RetryableError;
+
 #[derive(Error, Debug)]
 pub struct ReqwestError {
     pub msg: String,
@@ -37,6 +39,10 @@ pub enum RPCError {
     SetupError(String),
     #[error("Request error: {0}")]
     RequestError(RequestError),
+    /// The provider throttled the request. `retry_after` is the backoff suggested by the
+    /// provider, if any.
+    #[error("Rate limited: {msg}")]
+    RateLimited { msg: String, retry_after: Option<Duration> },
     #[error("Tracing failure: {0}")]
     TracingFailure(String),
     #[error("Unknown error: {0}")]
@@ -48,6 +54,12 @@ impl RPCError {
         msg: S,
         error: AlloyRpcError<TransportErrorKind>,
     ) -> Self {
+        if error.is_rate_limited() {
+            return RPCError::RateLimited {
+                msg: format!("{}: {error}", msg.to_string()),
+                retry_after: error.backoff_hint(),
+            };
+        }
         RPCError::RequestError(RequestError::Reqwest(ReqwestError {
             msg: msg.to_string(),
             source: error,
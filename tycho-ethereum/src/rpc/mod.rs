@@ -1,32 +1,38 @@
 use std::{
-    collections::{BTreeMap, HashMap},
+    collections::{BTreeMap, HashMap, HashSet},
     default::Default,
+    future::Future,
     iter::IntoIterator,
-    time::Duration,
+    sync::Arc,
+    time::{Duration, Instant},
 };
 
 use alloy::{
-    primitives::{private::serde, Address, B256, U256},
+    primitives::{private::serde, Address, B256, U256, U64},
     rpc::{
-        client::{ClientBuilder, ReqwestClient},
+        client::{BatchRequest, ClientBuilder, ReqwestClient},
+        json_rpc::ErrorPayload,
         types::{
             debug::{StorageMap, StorageRangeResult, StorageResult},
             trace::{
                 geth::GethTrace,
                 parity::{TraceResults, TraceType},
             },
-            AccessListResult, Block, BlockId, BlockNumberOrTag, TransactionRequest,
+            AccessListResult, Block, BlockId, BlockNumberOrTag, EIP1186AccountProofResponse,
+            TransactionRequest,
         },
     },
     transports::{http::reqwest, RpcError, TransportErrorKind, TransportResult},
 };
 use async_trait::async_trait;
 use backoff::backoff::Backoff;
-use futures::future::join_all;
+use futures::future::{join_all, try_join_all};
+use metrics::{counter, histogram};
 use num_bigint::BigUint;
 use serde::Deserialize;
 use serde_json::{json, Value};
-use tracing::{debug, info, instrument, trace, Span};
+use tokio::sync::{Semaphore, SemaphorePermit};
+use tracing::{debug, info, instrument, trace, warn, Span};
 use tycho_common::{traits::FeePriceGetter, Bytes};
 
 use crate::{gas::BlockGasPrice, RPCError, RequestError};
@@ -45,6 +51,37 @@ use crate::{
     },
 };
 
+/// Code, balance and nonce of an account. The code is `None` if it was not requested and the nonce
+/// is `None` if the provider can't return it.
+pub(crate) type AccountInfo = (Option<Bytes>, U256, Option<u64>);
+
+/// Calls batched per account when fetching [AccountInfo]: code, balance and nonce.
+const CALLS_PER_ACCOUNT: usize = 3;
+
+/// Maps the result of an `eth_getTransactionCount` call to `None` if the provider does not support
+/// the method or returned no result. Any other error, e.g. a transport error or rate limiting, is
+/// returned as is.
+fn nonce_if_available(
+    address: Address,
+    nonce: TransportResult<U64>,
+) -> TransportResult<Option<u64>> {
+    match nonce {
+        Ok(nonce) => Ok(Some(nonce.to::<u64>())),
+        // No result, "method not found" or "method not supported"
+        Err(error)
+            if matches!(
+                &error,
+                RpcError::NullResp |
+                    RpcError::ErrorResp(ErrorPayload { code: -32601 | -32604, .. })
+            ) =>
+        {
+            warn!(%address, %error, "Nonce not available, leaving it unset");
+            Ok(None)
+        }
+        Err(error) => Err(error),
+    }
+}
+
 /// This struct wraps the ReqwestClient and provides Ethereum-specific RPC methods
 /// with default batching support and retry logic.
 /// It is cheap to clone, as the `inner` internally uses an Arc for the ReqwestClient.
@@ -53,6 +90,8 @@ pub struct EthereumRpcClient {
     inner: ReqwestClient,
     batching: RPCBatchingConfig,
     retry_policy: RetryPolicy,
+    /// Bounds the number of in-flight requests across all clones of this client, if set.
+    request_limiter: Option<Arc<Semaphore>>,
     url: String,
 }
 
@@ -83,7 +122,13 @@ impl EthereumRpcClient {
 
         let retry_policy = RPCRetryConfig::default().into();
 
-        Ok(Self { inner: rpc, batching, retry_policy, url: rpc_url.to_string() })
+        Ok(Self {
+            inner: rpc,
+            batching,
+            retry_policy,
+            request_limiter: None,
+            url: rpc_url.to_string(),
+        })
     }
 
     pub fn get_url(&self) -> &str {
@@ -104,10 +149,49 @@ impl EthereumRpcClient {
         self
     }
 
+    /// Limits the number of requests in flight at any time, shared by all clones of this client.
+    /// A batch request counts as a single request. Retries wait for a free slot like any other
+    /// request, but no slot is held while backing off. Unlimited by default.
+    pub fn with_max_concurrent_requests(mut self, max_concurrent_requests: usize) -> Self {
+        self.request_limiter = Some(Arc::new(Semaphore::new(max_concurrent_requests.max(1))));
+        self
+    }
+
+    /// Waits for a free request slot if the number of concurrent requests is limited.
+    async fn acquire_request_permit(&self) -> Option<SemaphorePermit<'_>> {
+        match &self.request_limiter {
+            Some(limiter) => Some(
+                limiter
+                    .acquire()
+                    .await
+                    .expect("request limiter is never closed"),
+            ),
+            None => None,
+        }
+    }
+
+    /// Executes an RPC request with the client's retry policy, holding a request slot for the
+    /// duration of each attempt.
+    async fn retry_request<F, Fut, T, E>(&self, mut operation: F) -> Result<T, E>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: RetryableError,
+    {
+        self.retry_policy
+            .retry_request(|| {
+                let fut = operation();
+                async move {
+                    let _permit = self.acquire_request_permit().await;
+                    fut.await
+                }
+            })
+            .await
+    }
+
     #[instrument(level = "debug", skip(self))]
     pub async fn get_block_number(&self) -> Result<u64, RPCError> {
         let block_number = self
-            .retry_policy
             .retry_request(|| async {
                 self.inner
                     .request_noparams("eth_blockNumber")
@@ -169,7 +253,6 @@ impl EthereumRpcClient {
 
         // Fall back to legacy eth_gasPrice for non-EIP-1559 chains or if priority fee fetch fails
         let gas_price: U256 = self
-            .retry_policy
             .retry_request(|| async {
                 self.inner
                     .request_noparams("eth_gasPrice")
@@ -194,14 +277,13 @@ impl EthereumRpcClient {
     /// The max priority fee per gas in wei.
     #[instrument(level = "debug", skip(self))]
     async fn eth_max_priority_fee_per_gas(&self) -> Result<U256, RPCError> {
-        self.retry_policy
-            .retry_request(|| async {
-                self.inner
-                    .request_noparams("eth_maxPriorityFeePerGas")
-                    .await
-            })
-            .await
-            .map_err(|e| RPCError::from_alloy("Failed to get max priority fee per gas", e))
+        self.retry_request(|| async {
+            self.inner
+                .request_noparams("eth_maxPriorityFeePerGas")
+                .await
+        })
+        .await
+        .map_err(|e| RPCError::from_alloy("Failed to get max priority fee per gas", e))
     }
 
     #[instrument(level = "debug", skip(self))]
@@ -212,7 +294,6 @@ impl EthereumRpcClient {
         let full_tx_objects = false;
 
         let result: Option<Block> = self
-            .retry_policy
             .retry_request(|| async {
                 self.inner
                     .request("eth_getBlockByNumber", (block_id, full_tx_objects))
@@ -231,43 +312,142 @@ impl EthereumRpcClient {
     #[instrument(level = "debug", skip(self))]
     pub(crate) async fn eth_get_balance(
         &self,
-        block_id: BlockNumberOrTag,
+        block_id: BlockId,
         address: Address,
     ) -> Result<U256, RPCError> {
-        self.retry_policy
-            .retry_request(|| async {
-                self.inner
-                    .request("eth_getBalance", (address, block_id))
-                    .await
-            })
-            .await
-            .map_err(|e| {
-                RPCError::from_alloy(
-                    format!("Failed to get balance for address {address}, block {block_id}"),
-                    e,
-                )
-            })
+        self.retry_request(|| async {
+            self.inner
+                .request("eth_getBalance", (address, block_id))
+                .await
+        })
+        .await
+        .map_err(|e| {
+            RPCError::from_alloy(
+                format!("Failed to get balance for address {address}, block {block_id}"),
+                e,
+            )
+        })
     }
 
     #[instrument(level = "debug", skip(self))]
     pub(crate) async fn eth_get_code(
         &self,
-        block_id: BlockNumberOrTag,
+        block_id: BlockId,
         address: Address,
     ) -> Result<Bytes, RPCError> {
-        self.retry_policy
+        self.retry_request(|| async {
+            self.inner
+                .request("eth_getCode", (address, block_id))
+                .await
+        })
+        .await
+        .map_err(|e| {
+            RPCError::from_alloy(
+                format!("Failed to get code for address {address}, block {block_id}"),
+                e,
+            )
+        })
+    }
+
+    /// Returns the nonce of an account, or `None` if the provider does not support
+    /// `eth_getTransactionCount` or returned no result.
+    #[instrument(level = "debug", skip(self))]
+    pub(crate) async fn eth_get_transaction_count(
+        &self,
+        block_id: BlockId,
+        address: Address,
+    ) -> Result<Option<u64>, RPCError> {
+        let nonce = self
             .retry_request(|| async {
                 self.inner
-                    .request("eth_getCode", (address, block_id))
+                    .request::<_, U64>("eth_getTransactionCount", (address, block_id))
                     .await
             })
-            .await
-            .map_err(|e| {
-                RPCError::from_alloy(
-                    format!("Failed to get code for address {address}, block {block_id}"),
-                    e,
-                )
-            })
+            .await;
+        nonce_if_available(address, nonce).map_err(|e| {
+            RPCError::from_alloy(
+                format!("Failed to get nonce for address {address}, block {block_id}"),
+                e,
+            )
+        })
+    }
+
+    /// Returns the balance, code hash and the given storage slots of an account, together with
+    /// the Merkle proofs required to verify them against the block's state root.
+    /// See https://eips.ethereum.org/EIPS/eip-1186
+    #[instrument(level = "debug", skip(self, slots))]
+    pub(crate) async fn eth_get_proof(
+        &self,
+        block_id: BlockId,
+        address: Address,
+        slots: &[B256],
+    ) -> Result<EIP1186AccountProofResponse, RPCError> {
+        self.retry_request(|| async {
+            self.inner
+                .request("eth_getProof", (address, slots, block_id))
+                .await
+        })
+        .await
+        .map_err(|e| {
+            RPCError::from_alloy(
+                format!("Failed to get proof for address {address}, block {block_id}"),
+                e,
+            )
+        })
+    }
+
+    /// Returns the class hash of the contract deployed at the given address on Starknet.
+    /// See https://github.com/starkware-libs/starknet-specs (`starknet_getClassHashAt`)
+    ///
+    /// The block id, address and the returned class hash are hex encoded field elements.
+    #[instrument(level = "debug", skip(self))]
+    pub(crate) async fn starknet_get_class_hash_at(
+        &self,
+        block_id: &Value,
+        contract_address: &str,
+    ) -> Result<String, RPCError> {
+        self.retry_request(|| async {
+            self.inner
+                .request("starknet_getClassHashAt", (block_id, contract_address))
+                .await
+        })
+        .await
+        .map_err(|e| {
+            RPCError::from_alloy(
+                format!(
+                    "Failed to get class hash for address {contract_address}, block {block_id}"
+                ),
+                e,
+            )
+        })
+    }
+
+    /// Returns the value of a storage key of a Starknet contract.
+    /// See https://github.com/starkware-libs/starknet-specs (`starknet_getStorageAt`)
+    ///
+    /// The block id, address, key and the returned value are hex encoded field elements.
+    #[instrument(level = "debug", skip(self))]
+    pub(crate) async fn starknet_get_storage_at(
+        &self,
+        block_id: &Value,
+        contract_address: &str,
+        key: &str,
+    ) -> Result<String, RPCError> {
+        self.retry_request(|| async {
+            self.inner
+                .request("starknet_getStorageAt", (contract_address, key, block_id))
+                .await
+        })
+        .await
+        .map_err(|e| {
+            RPCError::from_alloy(
+                format!(
+                    "Failed to get storage for address {contract_address}, block {block_id}, \
+                     key {key}"
+                ),
+                e,
+            )
+        })
     }
 
     #[instrument(level = "debug", skip(self))]
@@ -307,7 +487,6 @@ impl EthereumRpcClient {
 
         // Use the wrapper type to handle nodes that return null instead of {} for empty storage
         let wrapper: StorageRangeResultWrapper = self
-            .retry_policy
             .retry_request(|| async {
                 self.inner
                     .request("debug_storageRangeAt", params)
@@ -324,51 +503,96 @@ impl EthereumRpcClient {
         Ok(wrapper.into())
     }
 
+    /// Retrieves the full storage of an account using `debug_storageRangeAt`.
+    ///
+    /// The hashed key space is split into `concurrency` equally sized sub-ranges that are paginated
+    /// concurrently. A concurrency of 1 scans the whole key space sequentially.
     #[instrument(level = "debug", skip(self), fields(slot_count = tracing::field::Empty))]
     pub(crate) async fn get_storage_range(
         &self,
         address: Address,
         block_hash: B256,
+        concurrency: usize,
     ) -> Result<HashMap<B256, B256>, RPCError> {
         let span = Span::current();
+        let concurrency = concurrency.max(1);
+        let step = U256::MAX / U256::from(concurrency);
+        let sub_ranges = (0..concurrency).map(|i| {
+            let start_key = B256::from(step * U256::from(i));
+            let end_key = (i + 1 < concurrency).then(|| B256::from(step * U256::from(i + 1)));
+            self.get_storage_sub_range(address, block_hash, start_key, end_key)
+        });
+
         let mut all_slots = HashMap::new();
-        let mut start_key = B256::ZERO;
+        for slots in try_join_all(sub_ranges).await? {
+            all_slots.extend(slots);
+        }
+
+        let slot_count = all_slots.len();
+        span.record("slot_count", slot_count as u64);
+        Ok(all_slots)
+    }
+
+    /// Paginates through the storage entries whose hashed key lies in `[start_key, end_key)`. If
+    /// `end_key` is `None` the scan continues until the end of the key space.
+    async fn get_storage_sub_range(
+        &self,
+        address: Address,
+        block_hash: B256,
+        mut start_key: B256,
+        end_key: Option<B256>,
+    ) -> Result<HashMap<B256, B256>, RPCError> {
+        let mut slots = HashMap::new();
         loop {
             trace!("Requesting storage range for {:?}, block: {:?}", address, block_hash);
             let result = self
                 .debug_storage_range_at(block_hash, address, start_key)
                 .await?;
 
-            for (_, entry) in result.storage.0 {
-                all_slots.insert(entry.key, entry.value);
+            // Entries are sorted by hashed key, anything past the end belongs to the next range.
+            for (hashed_key, entry) in result.storage.0 {
+                if end_key.is_some_and(|end_key| hashed_key >= end_key) {
+                    break;
+                }
+                slots.insert(entry.key, entry.value);
             }
 
-            if let Some(next_key) = result.next_key {
-                start_key = next_key;
-            } else {
-                break;
+            match result.next_key {
+                Some(next_key) if end_key.is_none_or(|end_key| next_key < end_key) => {
+                    start_key = next_key;
+                }
+                _ => break,
             }
         }
 
-        let slot_count = all_slots.len();
-        span.record("slot_count", slot_count as u64);
-        Ok(all_slots)
+        Ok(slots)
     }
 
     async fn non_batch_fetch_accounts_code_and_balance(
         &self,
-        block_id: BlockNumberOrTag,
+        block_id: BlockId,
         addresses: &[Address],
-    ) -> Result<HashMap<Address, (Bytes, U256)>, RPCError> {
+        skip_code: &HashSet<Address>,
+    ) -> Result<HashMap<Address, AccountInfo>, RPCError> {
         Ok(futures::future::try_join_all(
             addresses
                 .iter()
                 .map(|&address| async move {
-                    let (code, balance) = tokio::try_join!(
-                        self.eth_get_code(block_id, address),
-                        self.eth_get_balance(block_id, address)
-                    )?;
-                    Ok::<_, RPCError>((address, (code, balance)))
+                    let code = async {
+                        if skip_code.contains(&address) {
+                            Ok(None)
+                        } else {
+                            self.eth_get_code(block_id, address)
+                                .await
+                                .map(Some)
+                        }
+                    };
+                    let (code, balance, nonce) = tokio::join!(
+                        code,
+                        self.eth_get_balance(block_id, address),
+                        self.eth_get_transaction_count(block_id, address)
+                    );
+                    Ok::<_, RPCError>((address, (code?, balance?, nonce?)))
                 }),
         )
         .await?
@@ -376,32 +600,41 @@ impl EthereumRpcClient {
         .collect())
     }
 
-    #[instrument(level = "debug", skip(self))]
+    /// Fetches the code, balance and nonce of the given accounts. The code is not fetched for the
+    /// addresses in `skip_code`, e.g. because it is already known.
+    #[instrument(level = "debug", skip(self, skip_code))]
     pub(crate) async fn fetch_accounts_code_and_balance(
         &self,
-        block_id: BlockNumberOrTag,
+        block_id: BlockId,
         addresses: &[Address],
-    ) -> Result<HashMap<Address, (Bytes, U256)>, RPCError> {
+        skip_code: &HashSet<Address>,
+    ) -> Result<HashMap<Address, AccountInfo>, RPCError> {
         if let Some(max_batch_size) = self.batching.max_batch_size() {
-            self.batch_fetch_accounts_code_and_balance(block_id, addresses, max_batch_size)
-                .await
+            self.batch_fetch_accounts_code_and_balance(
+                block_id,
+                addresses,
+                skip_code,
+                max_batch_size,
+            )
+            .await
         } else {
-            self.non_batch_fetch_accounts_code_and_balance(block_id, addresses)
+            self.non_batch_fetch_accounts_code_and_balance(block_id, addresses, skip_code)
                 .await
         }
     }
 
     async fn batch_fetch_accounts_code_and_balance(
         &self,
-        block_id: BlockNumberOrTag,
+        block_id: BlockId,
         addresses: &[Address],
+        skip_code: &HashSet<Address>,
         batch_size: usize,
-    ) -> Result<HashMap<Address, (Bytes, U256)>, RPCError> {
-        let chunk_size = batch_size / 2; // we make 2 requests in a batch call: code + balance
+    ) -> Result<HashMap<Address, AccountInfo>, RPCError> {
+        let chunk_size = batch_size / CALLS_PER_ACCOUNT;
         if chunk_size == 0 {
-            return Err(RPCError::SetupError(
-                "BatchingConfig max_batch_size must be at least 2".to_string(),
-            ));
+            return Err(RPCError::SetupError(format!(
+                "BatchingConfig max_batch_size must be at least {CALLS_PER_ACCOUNT}"
+            )));
         }
 
         debug!(
@@ -415,66 +648,54 @@ impl EthereumRpcClient {
 
         // perf: consider running multiple batches in parallel using map of futures
         for chunk_addresses in addresses.chunks(chunk_size) {
-            let batch_call = || async {
-                let mut batch = self.inner.new_batch();
+            // code is not requested for addresses in `skip_code`
+            let skipped_code = chunk_addresses
+                .iter()
+                .filter(|address| skip_code.contains(*address))
+                .count();
+            debug!(
+                total_requests = chunk_addresses.len() * CALLS_PER_ACCOUNT - skipped_code,
+                block_id = block_id.to_string(),
+                "Sending batch request to RPC provider"
+            );
 
-                let code_balance_requests = chunk_addresses
-                    .iter()
-                    .map(|&address| {
-                        Ok((
-                            batch.add_call::<_, Bytes>("eth_getCode", &(address, block_id))?,
-                            batch.add_call::<_, U256>("eth_getBalance", &(address, block_id))?,
-                        ))
+            let chunk_results = self
+                .batch_call_with_partial_retry("code_balance", chunk_addresses, |batch, &address| {
+                    let code = (!skip_code.contains(&address))
+                        .then(|| batch.add_call::<_, Bytes>("eth_getCode", &(address, block_id)))
+                        .transpose()?;
+                    let balance =
+                        batch.add_call::<_, U256>("eth_getBalance", &(address, block_id))?;
+                    let nonce =
+                        batch.add_call::<_, U64>("eth_getTransactionCount", &(address, block_id))?;
+                    Ok(async move {
+                        let code = match code {
+                            Some(code) => Some(code.await?),
+                            None => None,
+                        };
+                        Ok((code, balance.await?, nonce_if_available(address, nonce.await)?))
                     })
-                    .collect::<Result<Vec<_>, RpcError<TransportErrorKind>>>()?;
-
-                debug!(
-                    total_requests = chunk_size * 2, // code + balance for each batch call
-                    block_id = block_id.to_string(),
-                    "Sending batch request to RPC provider"
-                );
-
-                batch.send().await?;
-
-                info!(
-                    chunk_size = chunk_addresses.len(),
-                    block_id = block_id.to_string(),
-                    "Successfully sent batch request for account code and balance"
-                );
-
-                let mut code_balance_res = Vec::with_capacity(chunk_addresses.len());
-                for (code_fut, balance_fut) in code_balance_requests {
-                    let code = code_fut.await?;
-                    let balance = balance_fut.await?;
-                    code_balance_res.push((code, balance));
-                }
-
-                Ok(code_balance_res)
-            };
-
-            // perf: current retry logic retries the entire batch on any retriable failure. Instead:
-            // 1. Only retry failed requests, not successful ones
-            // 2. Fail fast if any request has a non-retryable error (currently we only check the
-            //    first error encountered, potentially missing fatal errors in other requests)
-            let chunk_results = self.retry_policy.retry_request(batch_call).await
-            .map_err(|e| {
-                    let printable_addresses = chunk_addresses
+                })
+                .await
+                .map_err(|(addresses, e)| {
+                    let printable_addresses = addresses
                         .iter()
                         .map(|addr| format!("{:?}", addr))
                         .collect::<Vec<String>>()
                         .join(", ");
                     RPCError::from_alloy(format!(
                         "Failed to send batch request for code & balance for block {block_id}, address count {}, addresses [{printable_addresses}]",
-                        chunk_addresses.len(),
+                        addresses.len(),
                     ), e)
                 })?;
 
-            for (value, &address) in chunk_results
-                .into_iter()
-                .zip(chunk_addresses.iter())
-            {
-                result.insert(address, value);
-            }
+            info!(
+                chunk_size = chunk_addresses.len(),
+                block_id = block_id.to_string(),
+                "Successfully sent batch request for account code and balance"
+            );
+
+            result.extend(chunk_results);
         }
 
         Ok(result)
@@ -483,7 +704,7 @@ impl EthereumRpcClient {
     #[instrument(level = "debug", skip(self, slots))]
     pub(crate) async fn get_selected_storage(
         &self,
-        block_id: BlockNumberOrTag,
+        block_id: BlockId,
         address: Address,
         slots: &[B256],
     ) -> Result<HashMap<B256, Option<B256>>, RPCError> {
@@ -501,15 +722,14 @@ impl EthereumRpcClient {
 
     async fn non_batch_get_selected_storage(
         &self,
-        block_id: BlockNumberOrTag,
+        block_id: BlockId,
         address: Address,
         slots: &[B256],
     ) -> Result<HashMap<B256, Option<B256>>, RPCError> {
         let mut result = HashMap::with_capacity(slots.len());
 
         for slot in slots {
-            let storage_value = self.retry_policy
-            .retry_request(|| async {
+            let storage_value = self.retry_request(|| async {
                 self
                     .inner
                     .request("eth_getStorageAt", (&address, slot, block_id))
@@ -533,7 +753,7 @@ impl EthereumRpcClient {
 
     async fn batch_get_selected_storage(
         &self,
-        block_id: BlockNumberOrTag,
+        block_id: BlockId,
         address: Address,
         slots: &[B256],
         batch_size: usize,
@@ -541,40 +761,21 @@ impl EthereumRpcClient {
         let mut result = HashMap::with_capacity(slots.len());
 
         let chunk_size = batch_size; // we make 1 request in a batch call
+        if chunk_size == 0 {
+            return Err(RPCError::SetupError(
+                "BatchingConfig storage slot batch size must be at least 1".to_string(),
+            ));
+        }
 
         // perf: consider running multiple batches in parallel using map of futures
         for slot_batch in slots.chunks(chunk_size) {
-            let batch_call = || async {
-                let mut batch = self.inner.new_batch();
-
-                let mut storage_requests = slot_batch
-                    .iter()
-                    .map(|slot| {
-                        batch.add_call::<_, B256>("eth_getStorageAt", &(&address, slot, block_id))
-                    })
-                    .collect::<Result<Vec<_>, RpcError<TransportErrorKind>>>()?;
-
-                batch.send().await?;
-
-                let mut storage_res = Vec::with_capacity(slot_batch.len());
-                for storage_fut in storage_requests.iter_mut() {
-                    let storage_value = storage_fut.await?;
-                    storage_res.push(storage_value);
-                }
-
-                Ok(storage_res)
-            };
-
-            // perf: current retry logic retries the entire batch on any retriable failure. Instead:
-            // 1. Only retry failed requests, not successful ones
-            // 2. Fail fast if any request has a non-retryable error (currently we only check the
-            //    first error encountered, potentially missing fatal errors in other requests)
-            let chunk_res = self
-                .retry_policy
-                .retry_request(batch_call)
+            let chunk_results = self
+                .batch_call_with_partial_retry("storage", slot_batch, |batch, slot| {
+                    batch.add_call::<_, B256>("eth_getStorageAt", &(&address, slot, block_id))
+                })
                 .await
-                .map_err(|e| {
-                    let printable_slots = slot_batch
+                .map_err(|(slots, e)| {
+                    let printable_slots = slots
                         .iter()
                         .map(|slot| format!("{:?}", slot))
                         .collect::<Vec<String>>()
@@ -587,18 +788,91 @@ impl EthereumRpcClient {
                     )
                 })?;
 
-            for (storage_result, &address) in chunk_res
-                .into_iter()
-                .zip(slot_batch.iter())
-            {
+            for (slot, storage_result) in chunk_results {
                 let value = if storage_result == [0; 32] { None } else { Some(storage_result) };
-                result.insert(address, value);
+                result.insert(slot, value);
             }
         }
 
         Ok(result)
     }
 
+    /// Sends the calls added by `add_calls` for each item in a single batch request and returns
+    /// each item with its result.
+    ///
+    /// Unlike retrying the whole batch, only the items whose calls failed with a retryable error
+    /// are sent again, results that were already retrieved are kept. Any non-retryable error fails
+    /// right away. On failure, the items that could not be retrieved are returned with the error.
+    async fn batch_call_with_partial_retry<I, T, F, Fut>(
+        &self,
+        request_type: &'static str,
+        items: &[I],
+        add_calls: F,
+    ) -> Result<Vec<(I, T)>, (Vec<I>, RpcError<TransportErrorKind>)>
+    where
+        I: Clone,
+        F: Fn(&mut BatchRequest<'_>, &I) -> TransportResult<Fut>,
+        Fut: Future<Output = TransportResult<T>>,
+    {
+        let mut result = Vec::with_capacity(items.len());
+        let mut pending = items.to_vec();
+        let mut policy = self.retry_policy.clone();
+
+        loop {
+            let batch_call = async {
+                let _permit = self.acquire_request_permit().await;
+                let mut batch = self.inner.new_batch();
+
+                let requests = pending
+                    .iter()
+                    .map(|item| add_calls(&mut batch, item))
+                    .collect::<TransportResult<Vec<_>>>()?;
+
+                batch.send().await?;
+
+                Ok::<_, RpcError<TransportErrorKind>>(join_all(requests).await)
+            };
+
+            counter!("rpc_batches_sent", "request_type" => request_type).increment(1);
+            let start = Instant::now();
+            let batch_result = batch_call.await;
+            histogram!("rpc_batch_duration_seconds", "request_type" => request_type)
+                .record(start.elapsed().as_secs_f64());
+
+            let retry_error = match batch_result {
+                Ok(responses) => {
+                    let mut failed = Vec::new();
+                    let mut retry_error = None;
+                    for (item, response) in pending.into_iter().zip(responses) {
+                        match response {
+                            Ok(value) => result.push((item, value)),
+                            Err(e) if e.is_retryable() => {
+                                failed.push(item);
+                                retry_error = Some(e);
+                            }
+                            Err(e) => return Err((vec![item], e)),
+                        }
+                    }
+                    pending = failed;
+                    retry_error
+                }
+                Err(e) if e.is_retryable() => Some(e),
+                Err(e) => return Err((pending, e)),
+            };
+
+            let Some(error) = retry_error else { return Ok(result) };
+            counter!("rpc_batch_retries", "request_type" => request_type).increment(1);
+            match policy.next_backoff() {
+                // Rate limited providers may ask for a longer backoff than ours.
+                Some(backoff) => {
+                    let hint = error.backoff_hint().unwrap_or_default();
+                    tokio::time::sleep(backoff.max(hint)).await;
+                }
+                None => return Err((pending, error)),
+            }
+        }
+    }
+
     /// Use the trace_callMany API to simulate multiple call requests applied together one after
     /// another. See https://openethereum.github.io/JSONRPC-trace-module#trace_callmany
     ///
@@ -614,16 +888,15 @@ impl EthereumRpcClient {
             .map(|request| (request, vec![TraceType::Trace]))
             .collect();
 
-        self.retry_policy
-            .retry_request(|| async {
-                self.inner
-                    .request("trace_callMany", (&trace_requests, block))
-                    .await
-            })
-            .await
-            .map_err(|e| {
-                RPCError::from_alloy(format!("Failed to get trace call many for block {block}"), e)
-            })
+        self.retry_request(|| async {
+            self.inner
+                .request("trace_callMany", (&trace_requests, block))
+                .await
+        })
+        .await
+        .map_err(|e| {
+            RPCError::from_alloy(format!("Failed to get trace call many for block {block}"), e)
+        })
     }
 
     /// Executes a new message call immediately without creating a transaction on the blockchain.
@@ -636,19 +909,15 @@ impl EthereumRpcClient {
         request: TransactionRequest,
         block: BlockNumberOrTag,
     ) -> Result<Bytes, RPCError> {
-        self.retry_policy
-            .retry_request(|| async {
-                self.inner
-                    .request("eth_call", (&request, block))
-                    .await
-            })
-            .await
-            .map_err(|e| {
-                RPCError::from_alloy(
-                    format!("Failed to send an eth_call request for block {block}"),
-                    e,
-                )
-            })
+        self.retry_request(|| async {
+            self.inner
+                .request("eth_call", (&request, block))
+                .await
+        })
+        .await
+        .map_err(|e| {
+            RPCError::from_alloy(format!("Failed to send an eth_call request for block {block}"), e)
+        })
     }
 
     #[instrument(level = "debug", skip(self, access_list_params, trace_call_params))]
@@ -714,7 +983,7 @@ impl EthereumRpcClient {
             Ok((access_list_data, pre_state_trace))
         };
 
-        self.retry_policy.retry_request(|| async {
+        self.retry_request(|| async {
             batch_call().await
         }).await
         .map_err(|e| {
@@ -804,7 +1073,6 @@ impl EthereumRpcClient {
             // 2. Fail fast if any request has a non-retryable error (currently we only check the
             //    first error encountered, potentially missing fatal errors in other requests)
             let chunk_results = self
-                .retry_policy
                 .retry_request(|| async { batch_call().await })
                 .await
                 .map_err(|e| {
@@ -852,10 +1120,16 @@ impl EthereumRpcClient {
         batch_size: usize,
     ) -> Result<Vec<TransportResult<Value>>, RPCError> {
         let chunk_size = batch_size; // we make 1 request per test
+        if chunk_size == 0 {
+            return Err(RPCError::SetupError(
+                "slot_detector_tests requires max_batch_size >= 1".to_string(),
+            ));
+        }
         let mut result = Vec::with_capacity(requests.len());
 
         for chunk_requests in requests.chunks(chunk_size) {
             let batch_call = || async {
+                let _permit = self.acquire_request_permit().await;
                 let mut batch = self.inner.new_batch();
 
                 let batch_calls = chunk_requests
@@ -1018,6 +1292,7 @@ mod tests {
                 inner: self.inner_rpc.clone(),
                 batching,
                 retry_policy,
+                request_limiter: None,
                 url: self.url.clone(),
             }
         }
@@ -1027,6 +1302,25 @@ mod tests {
         Address::from_str(address_str).expect("failed to parse address")
     }
 
+    #[rstest]
+    #[case::method_not_found(-32601, true)]
+    #[case::method_not_supported(-32604, true)]
+    #[case::rate_limited(429, false)]
+    #[case::internal_error(-32603, false)]
+    fn test_nonce_if_available_error_resp(#[case] code: i64, #[case] unavailable: bool) {
+        let error = RpcError::ErrorResp(ErrorPayload { code, message: "error".into(), data: None });
+
+        let nonce = nonce_if_available(Address::ZERO, Err(error));
+
+        assert_eq!(nonce.is_ok_and(|nonce| nonce.is_none()), unavailable);
+    }
+
+    #[test]
+    fn test_nonce_if_available() {
+        assert_eq!(nonce_if_available(Address::ZERO, Ok(U64::from(3))).unwrap(), Some(3));
+        assert_eq!(nonce_if_available(Address::ZERO, Err(RpcError::NullResp)).unwrap(), None);
+    }
+
     #[tokio::test]
     #[ignore = "require RPC connection"]
     async fn test_ethereum_rpc_client_creation() -> Result<(), RPCError> {
@@ -1075,6 +1369,41 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_max_concurrent_requests() {
+        let mut server = Server::new_async().await;
+        let mock = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x1f4"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let client = EthereumRpcClient::new(&server.url())
+            .expect("create client")
+            .with_max_concurrent_requests(1);
+
+        // Occupy the only request slot, the request must wait until it is released.
+        let permit = client.acquire_request_permit().await;
+        let request_client = client.clone();
+        let request = tokio::spawn(async move {
+            request_client
+                .eth_get_balance(BlockId::latest(), Address::ZERO)
+                .await
+        });
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!request.is_finished());
+
+        drop(permit);
+        let balance = request
+            .await
+            .expect("request task panicked")
+            .expect("get balance");
+
+        mock.assert();
+        assert_eq!(balance, U256::from(500));
+    }
+
     #[tokio::test]
     #[ignore = "require RPC connection"]
     async fn test_get_block_number() -> Result<(), RPCError> {
@@ -1107,7 +1436,7 @@ mod tests {
         let client = fixture.create_rpc_client(false);
 
         let address = Address::from_str(address_str).expect("failed to parse address");
-        let block_id = BlockNumberOrTag::Number(TEST_BLOCK_NUMBER);
+        let block_id = BlockId::number(TEST_BLOCK_NUMBER);
 
         let balance = client
             .eth_get_balance(block_id, address)
@@ -1138,7 +1467,7 @@ mod tests {
         let client = fixture.create_rpc_client(false);
 
         let address = parse_address(address_str);
-        let block_id = BlockNumberOrTag::Number(TEST_BLOCK_NUMBER);
+        let block_id = BlockId::number(TEST_BLOCK_NUMBER);
 
         let code = client
             .eth_get_code(block_id, address)
@@ -1167,6 +1496,67 @@ mod tests {
         Ok(())
     }
 
+    #[rstest]
+    #[tokio::test]
+    async fn test_get_storage_range_concurrent_matches_sequential(
+        #[values(1, 2)] concurrency: usize,
+    ) {
+        let mut server = Server::new_async().await;
+        let entry = |hashed_key: &str, slot: u8| {
+            json!({
+                format!("0x{hashed_key:0<64}"): {
+                    "key": B256::with_last_byte(slot),
+                    "value": B256::with_last_byte(slot + 10),
+                }
+            })
+        };
+        let storage = |entries: Vec<Value>| {
+            entries
+                .into_iter()
+                .flat_map(|e| e.as_object().unwrap().clone())
+                .collect::<serde_json::Map<_, _>>()
+        };
+        // Pages returned by the node for the different start keys. The range starting at the
+        // middle of the key space overlaps with the first page.
+        let pages = [
+            (
+                "0x0{64}",
+                storage(vec![entry("10", 1), entry("20", 2), entry("90", 3)]),
+                json!(format!("0x{:0<64}", "a0")),
+            ),
+            ("0xa0{63}", storage(vec![entry("a0", 4)]), Value::Null),
+            ("0x7f{63}", storage(vec![entry("90", 3), entry("a0", 4)]), Value::Null),
+        ];
+        for (start_key, storage, next_key) in pages {
+            server
+                .mock("POST", "/")
+                .match_body(mockito::Matcher::Regex(format!(r#""{start_key}",100000"#)))
+                .with_status(200)
+                .with_body(
+                    json!({
+                        "jsonrpc": "2.0",
+                        "id": 0,
+                        "result": { "storage": storage, "nextKey": next_key },
+                    })
+                    .to_string(),
+                )
+                .create_async()
+                .await;
+        }
+        let client =
+            EthereumRpcClient::new(&server.url()).expect("Failed to create EthereumRpcClient");
+
+        let result = client
+            .get_storage_range(Address::repeat_byte(0xaa), B256::repeat_byte(0xbb), concurrency)
+            .await
+            .expect("get storage range");
+
+        let expected = (1..=4)
+            .map(|slot| (B256::with_last_byte(slot), B256::with_last_byte(slot + 10)))
+            .collect::<HashMap<_, _>>();
+        assert_eq!(result, expected);
+    }
+
     #[rstest]
     #[case(BALANCER_VAULT_STR, BALANCER_VAULT_EXPECTED_SLOTS)]
     #[case(STETH_STR, STETH_EXPECTED_SLOTS)]
@@ -1176,6 +1566,7 @@ mod tests {
     async fn test_get_storage_range(
         #[case] address_str: &str,
         #[case] expected_slot_count: usize,
+        #[values(1, 4)] concurrency: usize,
     ) -> Result<(), RPCError> {
         let fixture = TestFixture::new();
         let client = fixture.create_rpc_client(false);
@@ -1192,7 +1583,7 @@ mod tests {
         let block_id = B256::from_str(TEST_BLOCK_HASH).expect("failed to parse block hash");
 
         let storage = client
-            .get_storage_range(address, block_id)
+            .get_storage_range(address, block_id, concurrency)
             .await?;
 
         assert_eq!(
@@ -1222,8 +1613,9 @@ mod tests {
 
         let codes_and_balances = client
             .fetch_accounts_code_and_balance(
-                BlockNumberOrTag::Number(fixture.block.number),
+                BlockId::number(fixture.block.number),
                 &requests,
+                &HashSet::new(),
             )
             .await?;
 
@@ -1243,11 +1635,15 @@ mod tests {
             .get(&first_address)
             .unwrap()
             .0
+            .as_ref()
+            .expect("code was requested")
             .is_empty());
         assert!(!codes_and_balances
             .get(&second_address)
             .unwrap()
             .0
+            .as_ref()
+            .expect("code was requested")
             .is_empty());
 
         Ok(())
@@ -1264,6 +1660,7 @@ mod tests {
             .get_storage_range(
                 parse_address(BALANCER_VAULT_STR),
                 B256::from_bytes(&fixture.block.hash),
+                1,
             )
             .await?;
 
@@ -1295,7 +1692,7 @@ mod tests {
 
         let storage = client
             .get_selected_storage(
-                BlockNumberOrTag::Number(fixture.block.number),
+                BlockId::number(fixture.block.number),
                 parse_address(BALANCER_VAULT_STR),
                 &slots_request,
             )
@@ -1734,9 +2131,8 @@ mod tests {
         let result = mock_batch_slot_detector_tests_call(&mut server).await;
 
         assert!(result.is_err());
-        // The error can be either RequestError (if the request itself fails) or
-        // InvalidResponse (if the response body can't be parsed as JSON)
-        assert!(matches!(result, Err(RPCError::RequestError(_))));
+        // The provider keeps answering with 429, so the error is reported as rate limiting
+        assert!(matches!(result, Err(RPCError::RateLimited { retry_after: None, .. })));
 
         m_failure.assert();
     }
@@ -1859,6 +2255,97 @@ mod tests {
         m.assert();
     }
 
+    async fn mock_batch_get_selected_storage_call(
+        server: &mut ServerGuard,
+    ) -> Result<HashMap<B256, Option<B256>>, RPCError> {
+        let policy = retry::tests::mock_retry_policy();
+
+        let rpc_client = EthereumRpcClient::new(&server.url())
+            .expect("Failed to create EthereumRpcClient")
+            .with_batching(RPCBatchingConfig::enabled_with_defaults())
+            .with_retry((&policy).into());
+
+        rpc_client
+            .get_selected_storage(
+                BlockId::number(1),
+                Address::ZERO,
+                &[B256::with_last_byte(1), B256::with_last_byte(2)],
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_batch_get_selected_storage_retries_only_failed_requests() {
+        let mut server = Server::new_async().await;
+
+        // First attempt: the first slot succeeds, the second is rate limited
+        let m1 = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(format!("{}", B256::with_last_byte(1))))
+            .with_status(200)
+            .with_body(format!(
+                r#"[
+                {{"jsonrpc":"2.0","id":0,"result":"{}"}},
+                {{"jsonrpc":"2.0","id":1,"error":{{"code":-32005,"message":"limit exceeded"}}}}
+            ]"#,
+                B256::with_last_byte(5)
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+
+        // Second attempt: only the failed slot is requested again
+        let m2 = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(format!("{}", B256::with_last_byte(2))),
+                mockito::Matcher::Regex(r#""id":2"#.to_string()),
+            ]))
+            .with_status(200)
+            .with_body(r#"[{"jsonrpc":"2.0","id":2,"result":"0x0000000000000000000000000000000000000000000000000000000000000000"}]"#)
+            .expect(1)
+            .create_async()
+            .await;
+
+        let result = mock_batch_get_selected_storage_call(&mut server)
+            .await
+            .expect("get storage");
+
+        m1.assert();
+        m2.assert();
+        assert_eq!(
+            result,
+            HashMap::from([
+                (B256::with_last_byte(1), Some(B256::with_last_byte(5))),
+                (B256::with_last_byte(2), None),
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_batch_get_selected_storage_no_retry_on_non_retryable_error() {
+        let mut server = Server::new_async().await;
+
+        let m1 = server
+            .mock("POST", "/")
+            .with_status(200)
+            .with_body(format!(
+                r#"[
+                {{"jsonrpc":"2.0","id":0,"result":"{}"}},
+                {{"jsonrpc":"2.0","id":1,"error":{{"code":-32602,"message":"invalid params"}}}}
+            ]"#,
+                B256::with_last_byte(5)
+            ))
+            .expect(1)
+            .create_async()
+            .await;
+
+        let result = mock_batch_get_selected_storage_call(&mut server).await;
+
+        m1.assert();
+        assert!(matches!(result, Err(RPCError::RequestError(_))));
+    }
+
     #[tokio::test]
     async fn test_get_legacy_gas_price_mocked() {
         let mut server = Server::new_async().await;
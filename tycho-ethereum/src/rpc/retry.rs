@@ -49,6 +49,10 @@ pub(crate) trait RetryableError {
     /// This method extracts that hint if available.
     fn backoff_hint(&self) -> Option<Duration>;
 
+    /// Whether the provider rejected the request because of rate limiting, either with a `429`
+    /// HTTP status or with a JSON-RPC rate limit error code.
+    fn is_rate_limited(&self) -> bool;
+
     /// Converts the RPC error into a [backoff::Error] for retry logic.
     fn to_backoff(self) -> backoff::Error<Self>
     where
@@ -66,6 +70,12 @@ pub(crate) trait RetryableError {
     }
 }
 
+/// JSON-RPC error codes used by providers to signal rate limiting: `-32005` ("limit exceeded",
+/// e.g. Infura) and `429` (e.g. Alchemy).
+fn is_rate_limit_code(code: i64) -> bool {
+    matches!(code, -32005 | 429)
+}
+
 /// Extends alloy's default retry logic with additional error code classification.
 ///
 /// This function is used in combination with alloy's [`ErrorPayload::is_retry_err()`] to
@@ -140,6 +150,14 @@ impl<E: std::borrow::Borrow<RawValue>> RetryableError for RpcError<TransportErro
         }
         None
     }
+
+    fn is_rate_limited(&self) -> bool {
+        match self {
+            Self::Transport(TransportErrorKind::HttpError(err)) => err.status == 429,
+            Self::ErrorResp(resp) => is_rate_limit_code(resp.code),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -234,6 +252,7 @@ pub(crate) mod tests {
     use serde::de::Error;
 
     use super::*;
+    use crate::RPCError;
 
     pub(crate) const MOCK_RETRY_POLICY_MAX_ATTEMPTS: usize = 3;
 
@@ -422,6 +441,46 @@ pub(crate) mod tests {
         assert_eq!(hint, None);
     }
 
+    #[rstest]
+    #[case::http_429(TransportErrorKind::http_error(429, String::new()), true)]
+    #[case::http_503(TransportErrorKind::http_error(503, String::new()), false)]
+    #[case::limit_exceeded_code(
+        RpcError::ErrorResp(ErrorPayload { code: -32005, message: "limit exceeded".into(), data: None }),
+        true
+    )]
+    #[case::too_many_requests_code(
+        RpcError::ErrorResp(ErrorPayload { code: 429, message: "Too Many Requests".into(), data: None }),
+        true
+    )]
+    #[case::header_not_found_code(
+        RpcError::ErrorResp(ErrorPayload { code: -32000, message: "header not found".into(), data: None }),
+        false
+    )]
+    fn test_is_rate_limited(#[case] err: RpcError<TransportErrorKind>, #[case] expected: bool) {
+        assert_eq!(err.is_rate_limited(), expected);
+    }
+
+    #[test]
+    fn test_rate_limited_error_conversion() {
+        let data = serde_json::value::to_raw_value(&serde_json::json!({
+            "rate": { "backoff_seconds": 3 }
+        }))
+        .unwrap();
+        let err = RpcError::ErrorResp(ErrorPayload {
+            code: -32005,
+            message: "limit exceeded".into(),
+            data: Some(data),
+        });
+
+        let rpc_error = RPCError::from_alloy("Failed to get balance", err);
+
+        assert!(matches!(
+            rpc_error,
+            RPCError::RateLimited { retry_after: Some(duration), .. }
+                if duration == Duration::from_secs(3)
+        ));
+    }
+
     #[test]
     fn test_retry_policy_custom() {
         let exp_policy: ExponentialBackoff = ExponentialBackoffBuilder::new()
@@ -1,13 +1,23 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::Instant,
+};
 
 use alloy::{
-    primitives::{Address as AlloyAddress, B256},
-    rpc::types::{BlockId, BlockNumberOrTag},
+    primitives::{keccak256, Address as AlloyAddress, B256, KECCAK256_EMPTY},
+    rpc::types::{BlockId, BlockNumberOrTag, EIP1186AccountProofResponse},
 };
 use async_trait::async_trait;
 use chrono::DateTime;
-use futures::future::try_join_all;
-use tracing::{debug, info};
+use futures::{
+    future::{join_all, try_join_all},
+    stream, Future, StreamExt, TryStreamExt,
+};
+use lru::LruCache;
+use metrics::{counter, histogram};
+use tracing::{debug, info, instrument};
 use tycho_common::{
     models::{blockchain::Block, contract::AccountDelta, Chain, ChangeType},
     traits::{AccountExtractor, StorageSnapshotRequest},
@@ -22,6 +32,63 @@ use crate::{
     BytesCodec,
 };
 
+/// Merges the requests for the same address into a single request. The requested slots are
+/// unioned, and a request for the full storage (`None` slots) takes precedence over explicit slots.
+pub(crate) fn merge_requests(requests: &[StorageSnapshotRequest]) -> Vec<StorageSnapshotRequest> {
+    let mut merged: HashMap<&Bytes, Option<Vec<Bytes>>> = HashMap::new();
+    for request in requests {
+        match merged.entry(&request.address) {
+            Entry::Vacant(entry) => {
+                entry.insert(request.slots.clone());
+            }
+            Entry::Occupied(mut entry) => match (entry.get_mut(), &request.slots) {
+                (Some(slots), Some(new_slots)) => slots.extend(new_slots.iter().cloned()),
+                (slots, _) => *slots = None,
+            },
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|(address, mut slots)| {
+            if let Some(slots) = &mut slots {
+                let mut seen = HashSet::new();
+                slots.retain(|slot| seen.insert(slot.clone()));
+            }
+            StorageSnapshotRequest { address: address.clone(), slots }
+        })
+        .collect()
+}
+
+/// Strategy used by `EVMAccountExtractor` to retrieve account balances and storage slots.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum StorageExtractionStrategy {
+    /// Fetches code, balance and nonce with `eth_getCode`, `eth_getBalance` and
+    /// `eth_getTransactionCount`, and explicit slots with `eth_getStorageAt`.
+    #[default]
+    Standard,
+    /// Fetches balance, nonce, code hash and explicit slots with a single `eth_getProof` call per
+    /// account. Works on providers that do not expose the `debug` namespace. Code is only fetched
+    /// for accounts with a non-empty code hash. Requests without explicit slots still rely on
+    /// `debug_storageRangeAt`.
+    Proof,
+}
+
+/// Result of [`EVMAccountExtractor::get_accounts_at_block_partial`], keyed by account address.
+#[derive(Debug, Default)]
+pub struct PartialAccountDeltas {
+    pub succeeded: HashMap<Bytes, AccountDelta>,
+    pub failed: HashMap<Bytes, RPCError>,
+}
+
+/// Contract code cached by `EVMAccountExtractor`.
+struct CachedCode {
+    /// Earliest block the code was observed at.
+    first_seen_block: u64,
+    hash: B256,
+    code: Bytes,
+}
+
 /// `EVMAccountExtractor` is a struct that implements the `AccountExtractor` trait for Ethereum
 /// accounts.
 /// TODO: once the `chain` attribute is deprecated from AccountDelta,
@@ -30,57 +97,400 @@ use crate::{
 pub struct EVMAccountExtractor {
     rpc: EthereumRpcClient,
     chain: Chain,
+    /// Maximum number of accounts whose storage is fetched concurrently. Unlimited if `None`.
+    max_concurrency: Option<usize>,
+    /// Number of concurrent `debug_storageRangeAt` scans used to fetch the full storage of a
+    /// single account.
+    storage_range_concurrency: usize,
+    storage_strategy: StorageExtractionStrategy,
+    /// Code of previously extracted contracts, keyed by address. Disabled if `None`.
+    code_cache: Option<Mutex<LruCache<AlloyAddress, CachedCode>>>,
 }
 
 impl EVMAccountExtractor {
     pub fn new(client: &EthereumRpcClient, chain: Chain) -> Self {
         // As the client is a thin wrapper around an Arc, cloning is inexpensive.
-        Self { rpc: client.clone(), chain }
+        Self {
+            rpc: client.clone(),
+            chain,
+            max_concurrency: None,
+            storage_range_concurrency: 1,
+            storage_strategy: StorageExtractionStrategy::default(),
+            code_cache: None,
+        }
     }
 
-    pub async fn get_block_data(&self, block_id: u64) -> Result<Block, RPCError> {
-        let block_id = BlockId::from(block_id);
+    /// Limits how many accounts have their storage fetched concurrently, to avoid hitting the
+    /// provider's rate limits when snapshotting many accounts. Defaults to unlimited.
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = Some(max_concurrency.max(1));
+        self
+    }
 
-        let block = self
-            .rpc
-            .eth_get_block_by_number(block_id)
+    /// Splits the storage key space into this many sub-ranges that are scanned concurrently when
+    /// fetching the full storage of an account. Speeds up accounts with many slots. Defaults to 1,
+    /// a sequential scan.
+    pub fn with_storage_range_concurrency(mut self, concurrency: usize) -> Self {
+        self.storage_range_concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Selects how balances and storage slots are retrieved. Defaults to
+    /// [`StorageExtractionStrategy::Standard`].
+    pub fn with_storage_strategy(mut self, strategy: StorageExtractionStrategy) -> Self {
+        self.storage_strategy = strategy;
+        self
+    }
+
+    /// Caches the code of up to `size` contracts across calls, so that the code of already known
+    /// contracts is not fetched again. A size of 0 disables the cache, which is the default.
+    ///
+    /// With [`StorageExtractionStrategy::Proof`] cached code is only reused if its hash matches the
+    /// code hash of the account. With [`StorageExtractionStrategy::Standard`] the code hash is not
+    /// known up front, so cached code is only reused for blocks at or after the earliest block it
+    /// was observed at. This assumes deployed code does not change, which holds since EIP-6780
+    /// restricted `SELFDESTRUCT`.
+    pub fn with_code_cache_size(mut self, size: usize) -> Self {
+        self.code_cache = NonZeroUsize::new(size).map(|size| Mutex::new(LruCache::new(size)));
+        self
+    }
+
+    /// Returns the cached code of the given contract at `block_number`. If `code_hash` is given,
+    /// the cached code is returned if its hash matches. Otherwise, it is only returned if it was
+    /// observed at or before `block_number`.
+    fn cached_code(
+        &self,
+        address: &AlloyAddress,
+        block_number: u64,
+        code_hash: Option<B256>,
+    ) -> Option<Bytes> {
+        let mut cache = self
+            .code_cache
+            .as_ref()?
+            .lock()
+            .expect("code cache lock poisoned");
+        let code = cache
+            .get(address)
+            .filter(|cached| match code_hash {
+                Some(code_hash) => code_hash == cached.hash,
+                None => cached.first_seen_block <= block_number,
+            })
+            .map(|cached| cached.code.clone());
+
+        let chain = self.chain.to_string();
+        match code {
+            Some(_) => counter!("account_extractor_code_cache_hits", "chain" => chain).increment(1),
+            None => counter!("account_extractor_code_cache_misses", "chain" => chain).increment(1),
+        }
+        code
+    }
+
+    /// Caches the code of a contract observed at `block_number`. Accounts without code are not
+    /// cached, as a contract may still be deployed at their address.
+    fn cache_code(&self, address: AlloyAddress, block_number: u64, code: &Bytes) {
+        let Some(cache) = &self.code_cache else { return };
+        if code.is_empty() {
+            return;
+        }
+        let hash = keccak256(code);
+        let mut cache = cache
+            .lock()
+            .expect("code cache lock poisoned");
+        match cache.get_mut(&address) {
+            Some(cached) if cached.hash == hash => {
+                cached.first_seen_block = cached
+                    .first_seen_block
+                    .min(block_number);
+            }
+            _ => {
+                cache.put(
+                    address,
+                    CachedCode { first_seen_block: block_number, hash, code: code.clone() },
+                );
+            }
+        }
+    }
+
+    /// Extracts the requested accounts using `eth_getProof` and returns each account together
+    /// with its proof, so that callers can verify the balance and storage against the block's
+    /// state root.
+    pub async fn get_accounts_and_proofs_at_block(
+        &self,
+        block: &Block,
+        requests: &[StorageSnapshotRequest],
+    ) -> Result<HashMap<Bytes, (AccountDelta, EIP1186AccountProofResponse)>, RPCError> {
+        let accounts = self
+            .extract_accounts_and_proofs(block, BlockId::from(block.number), requests)
             .await?;
+        self.record_accounts(
+            accounts
+                .values()
+                .map(|(account, _)| account),
+        );
 
-        Ok(Block {
-            number: block.header.number,
-            hash: block.header.hash.to_bytes(),
-            parent_hash: block.header.parent_hash.to_bytes(),
-            chain: self.chain,
-            ts: DateTime::from_timestamp(block.header.timestamp as i64, 0)
-                .ok_or_else(|| {
-                    RPCError::RequestError(RequestError::Other("Invalid timestamp in block".into()))
-                })?
-                .naive_utc(),
-        })
+        Ok(accounts)
     }
-}
 
-#[async_trait]
-impl AccountExtractor for EVMAccountExtractor {
-    type Error = RPCError;
+    /// Extracts the requested accounts with `eth_getProof`, reading all values at `block_id`.
+    async fn extract_accounts_and_proofs(
+        &self,
+        block: &Block,
+        block_id: BlockId,
+        requests: &[StorageSnapshotRequest],
+    ) -> Result<HashMap<Bytes, (AccountDelta, EIP1186AccountProofResponse)>, RPCError> {
+        let block_hash = B256::from_slice(&block.hash);
 
-    async fn get_accounts_at_block(
+        // Merge requests for the same account to avoid making more requests than necessary.
+        let unique_requests = merge_requests(requests);
+
+        let account_futs = unique_requests
+            .iter()
+            .map(|req| async move {
+                let address = AlloyAddress::from_bytes(&req.address);
+                let slots = req
+                    .slots
+                    .iter()
+                    .flatten()
+                    .map(B256::from_bytes)
+                    .collect::<Vec<_>>();
+
+                let proof = self
+                    .rpc
+                    .eth_get_proof(block_id, address, &slots)
+                    .await?;
+
+                // The proof only contains the code hash, so the code is fetched separately.
+                let code = if proof.code_hash == KECCAK256_EMPTY {
+                    Bytes::new()
+                } else if let Some(code) =
+                    self.cached_code(&address, block.number, Some(proof.code_hash))
+                {
+                    code
+                } else {
+                    let code = self
+                        .rpc
+                        .eth_get_code(block_id, address)
+                        .await?;
+                    self.cache_code(address, block.number, &code);
+                    code
+                };
+
+                let storage = if req.slots.is_some() {
+                    proof
+                        .storage_proof
+                        .iter()
+                        .map(|slot| {
+                            let value = B256::from(slot.value);
+                            (
+                                slot.key.as_b256().to_bytes(),
+                                (!value.is_zero()).then(|| value.to_bytes()),
+                            )
+                        })
+                        .collect::<HashMap<_, _>>()
+                } else {
+                    self.rpc
+                        .get_storage_range(address, block_hash, self.storage_range_concurrency)
+                        .await?
+                        .into_iter()
+                        .map(|(k, v)| (k.to_bytes(), Some(v.to_bytes())))
+                        .collect()
+                };
+
+                let mut account_delta = AccountDelta::new(
+                    self.chain,
+                    address.to_bytes(),
+                    storage,
+                    Some(proof.balance.to_bytes()),
+                    Some(code),
+                    ChangeType::Creation,
+                );
+                account_delta.nonce = Some(proof.nonce);
+
+                Ok((address.to_bytes(), (account_delta, proof)))
+            })
+            .collect::<Vec<_>>();
+
+        let start = Instant::now();
+        let accounts = self.join_limited(account_futs).await?;
+        self.record_duration("proof", start);
+        debug!(block_number = block.number, "Successfully retrieved account proofs");
+
+        Ok(accounts.into_iter().collect())
+    }
+
+    /// Records the time spent fetching one type of account data.
+    fn record_duration(&self, request_type: &'static str, start: Instant) {
+        histogram!(
+            "account_extractor_duration_seconds",
+            "chain" => self.chain.to_string(),
+            "request_type" => request_type,
+        )
+        .record(start.elapsed().as_secs_f64());
+    }
+
+    /// Records metrics and logs the completion of an account extraction.
+    fn record_extraction(&self, block: &Block, accounts: &HashMap<Bytes, AccountDelta>) {
+        self.record_accounts(accounts.values());
+        info!(
+            total_accounts_processed = accounts.len(),
+            block_number = block.number,
+            strategy = ?self.storage_strategy,
+            "Completed account extraction successfully"
+        );
+    }
+
+    /// Records the number of extracted accounts and storage slots.
+    fn record_accounts<'a>(&self, accounts: impl Iterator<Item = &'a AccountDelta>) {
+        let chain = self.chain.to_string();
+        let mut total_accounts = 0;
+        for account in accounts {
+            total_accounts += 1;
+            counter!("account_extractor_slots_fetched", "chain" => chain.clone())
+                .increment(account.slots.len() as u64);
+            histogram!("account_extractor_slots_per_account", "chain" => chain.clone())
+                .record(account.slots.len() as f64);
+        }
+        counter!("account_extractor_accounts_fetched", "chain" => chain).increment(total_accounts);
+    }
+
+    /// Like [`AccountExtractor::get_accounts_at_block`], but a failure to extract an account does
+    /// not abort the extraction of the other accounts.
+    ///
+    /// All accounts are first extracted together, so that requests are batched. Only if that fails
+    /// are the accounts extracted one by one, to find out which of them fail.
+    pub async fn get_accounts_at_block_partial(
         &self,
         block: &Block,
         requests: &[StorageSnapshotRequest],
-    ) -> Result<HashMap<Bytes, AccountDelta>, Self::Error> {
-        let block_id = BlockNumberOrTag::Number(block.number);
+    ) -> PartialAccountDeltas {
+        let block_id = BlockId::from(block.number);
+        let requests = merge_requests(requests);
+
+        let mut accounts = PartialAccountDeltas::default();
+        match self
+            .extract_accounts(block, block_id, &requests)
+            .await
+        {
+            Ok(updates) => accounts.succeeded = updates,
+            Err(error) => {
+                debug!(%error, "Account extraction failed, extracting accounts one by one");
+                let account_futs = requests
+                    .iter()
+                    .map(|request| async move {
+                        let result = self
+                            .extract_accounts(block, block_id, std::slice::from_ref(request))
+                            .await
+                            .and_then(|mut updates| {
+                                updates
+                                    .remove(&request.address)
+                                    .ok_or_else(|| {
+                                        RPCError::UnknownError(format!(
+                                            "Account {} missing from extraction result",
+                                            request.address
+                                        ))
+                                    })
+                            });
+                        (request.address.clone(), result)
+                    });
+
+                // Each extraction fetches the storage of a single account, so `max_concurrency`
+                // is applied across accounts here.
+                let results = match self.max_concurrency {
+                    Some(limit) => {
+                        stream::iter(account_futs)
+                            .buffer_unordered(limit)
+                            .collect::<Vec<_>>()
+                            .await
+                    }
+                    None => join_all(account_futs).await,
+                };
+
+                for (address, result) in results {
+                    match result {
+                        Ok(account_delta) => {
+                            accounts
+                                .succeeded
+                                .insert(address, account_delta);
+                        }
+                        Err(error) => {
+                            accounts.failed.insert(address, error);
+                        }
+                    }
+                }
+            }
+        }
+        self.record_accounts(accounts.succeeded.values());
+
+        info!(
+            succeeded = accounts.succeeded.len(),
+            failed = accounts.failed.len(),
+            block_number = block.number,
+            "Completed partial account extraction"
+        );
+
+        accounts
+    }
+
+    /// Awaits all futures, running at most `max_concurrency` of them at a time.
+    async fn join_limited<T>(
+        &self,
+        futs: impl IntoIterator<Item = impl Future<Output = Result<T, RPCError>>>,
+    ) -> Result<Vec<T>, RPCError> {
+        match self.max_concurrency {
+            Some(limit) => {
+                stream::iter(futs)
+                    .buffered(limit)
+                    .try_collect()
+                    .await
+            }
+            None => try_join_all(futs).await,
+        }
+    }
+
+    /// Extracts the requested accounts at the block identified by `block_id`, e.g. `Latest` or
+    /// `Finalized`, and returns the resolved block together with the deltas.
+    ///
+    /// All values are read by the hash of the resolved block (EIP-1898), so they belong to the
+    /// returned block even if it is reorged out of the canonical chain in the meantime.
+    pub async fn get_accounts_at_block_id(
+        &self,
+        block_id: BlockNumberOrTag,
+        requests: &[StorageSnapshotRequest],
+    ) -> Result<(Block, HashMap<Bytes, AccountDelta>), RPCError> {
+        let block = self
+            .get_block(BlockId::from(block_id))
+            .await?;
+
+        let updates = self
+            .extract_accounts(&block, BlockId::hash(B256::from_slice(&block.hash)), requests)
+            .await?;
+        self.record_extraction(&block, &updates);
+
+        Ok((block, updates))
+    }
+
+    /// Extracts the requested accounts, reading all values at `block_id`.
+    async fn extract_accounts(
+        &self,
+        block: &Block,
+        block_id: BlockId,
+        requests: &[StorageSnapshotRequest],
+    ) -> Result<HashMap<Bytes, AccountDelta>, RPCError> {
+        if self.storage_strategy == StorageExtractionStrategy::Proof {
+            return Ok(self
+                .extract_accounts_and_proofs(block, block_id, requests)
+                .await?
+                .into_iter()
+                .map(|(address, (account_delta, _))| (address, account_delta))
+                .collect());
+        }
+
         let block_hash = B256::from_slice(&block.hash);
 
         let mut updates = HashMap::new();
 
-        // Remove duplicates to avoid making more requests than necessary.
-        let unique_requests: Vec<StorageSnapshotRequest> = requests
-            .iter()
-            .cloned()
-            .collect::<HashSet<_>>()
-            .into_iter()
-            .collect();
+        // Merge requests for the same account to avoid making more requests than necessary.
+        let unique_requests = merge_requests(requests);
 
         // Convert addresses to AlloyAddress for easier handling
         let alloy_addresses: Vec<AlloyAddress> = unique_requests
@@ -88,10 +498,20 @@ impl AccountExtractor for EVMAccountExtractor {
             .map(|request| AlloyAddress::from_bytes(&request.address))
             .collect();
 
+        // Only fetch the code of contracts that are not cached yet
+        let cached_codes: HashMap<AlloyAddress, Bytes> = alloy_addresses
+            .iter()
+            .filter_map(|address| {
+                self.cached_code(address, block.number, None)
+                    .map(|code| (*address, code))
+            })
+            .collect();
+        let skip_code: HashSet<AlloyAddress> = cached_codes.keys().copied().collect();
+
         // Create a future for code and balance retrieval
         let codes_and_balances_fut = self
             .rpc
-            .fetch_accounts_code_and_balance(block_id, &alloy_addresses);
+            .fetch_accounts_code_and_balance(block_id, &alloy_addresses, &skip_code);
 
         // Create futures for storage retrieval
         let storage_futs = unique_requests
@@ -111,7 +531,7 @@ impl AccountExtractor for EVMAccountExtractor {
                             .await
                     } else {
                         self.rpc
-                            .get_storage_range(address, block_hash)
+                            .get_storage_range(address, block_hash, self.storage_range_concurrency)
                             .await
                             // Wrap the resulting hashmap values in Some to match the expected type
                             .map(|result| {
@@ -127,17 +547,28 @@ impl AccountExtractor for EVMAccountExtractor {
             })
             .collect::<Vec<_>>();
 
+        let start = Instant::now();
         let codes_and_balances = codes_and_balances_fut.await?;
+        self.record_duration("code_balance", start);
         debug!(block_number = block.number, "Successfully retrieved account code and balance data");
 
-        let storage_results = try_join_all(storage_futs).await?;
+        let start = Instant::now();
+        let storage_results = self.join_limited(storage_futs).await?;
+        self.record_duration("storage", start);
         debug!(block_number = block.number, "Successfully retrieved account storage");
 
         for (address, storage_result) in alloy_addresses
             .iter()
             .zip(storage_results)
         {
-            let (code, balance) = codes_and_balances[address].clone();
+            let (code, balance, nonce) = codes_and_balances[address].clone();
+            let code = match code {
+                Some(code) => {
+                    self.cache_code(*address, block.number, &code);
+                    code
+                }
+                None => cached_codes[address].clone(),
+            };
 
             // Convert the storage result from Alloy to Tycho types
             let storage = storage_result
@@ -145,7 +576,7 @@ impl AccountExtractor for EVMAccountExtractor {
                 .map(|(k, v)| (k.to_bytes(), v.map(|v| v.to_bytes())))
                 .collect::<HashMap<_, _>>();
 
-            let account_delta = AccountDelta::new(
+            let mut account_delta = AccountDelta::new(
                 self.chain,
                 address.to_bytes(),
                 storage,
@@ -153,15 +584,61 @@ impl AccountExtractor for EVMAccountExtractor {
                 Some(code),
                 ChangeType::Creation,
             );
+            account_delta.nonce = nonce;
 
             updates.insert(address.to_bytes(), account_delta);
         }
 
-        info!(
-            total_accounts_processed = updates.len(),
-            block_number = block.number,
-            "Completed batch account extraction successfully"
-        );
+        Ok(updates)
+    }
+
+    /// Resolves the block identified by `block_id`, either a block number or a tag such as
+    /// `Latest` or `Finalized`.
+    pub async fn get_block_data(
+        &self,
+        block_id: impl Into<BlockNumberOrTag>,
+    ) -> Result<Block, RPCError> {
+        self.get_block(BlockId::from(block_id.into()))
+            .await
+    }
+
+    async fn get_block(&self, block_id: BlockId) -> Result<Block, RPCError> {
+        let block = self
+            .rpc
+            .eth_get_block_by_number(block_id)
+            .await?;
+
+        Ok(Block {
+            number: block.header.number,
+            hash: block.header.hash.to_bytes(),
+            parent_hash: block.header.parent_hash.to_bytes(),
+            chain: self.chain,
+            ts: DateTime::from_timestamp(block.header.timestamp as i64, 0)
+                .ok_or_else(|| {
+                    RPCError::RequestError(RequestError::Other("Invalid timestamp in block".into()))
+                })?
+                .naive_utc(),
+        })
+    }
+}
+
+#[async_trait]
+impl AccountExtractor for EVMAccountExtractor {
+    type Error = RPCError;
+
+    #[instrument(
+        skip_all,
+        fields(chain = %self.chain, block_number = block.number, requests = requests.len())
+    )]
+    async fn get_accounts_at_block(
+        &self,
+        block: &Block,
+        requests: &[StorageSnapshotRequest],
+    ) -> Result<HashMap<Bytes, AccountDelta>, Self::Error> {
+        let updates = self
+            .extract_accounts(block, BlockId::from(block.number), requests)
+            .await?;
+        self.record_extraction(block, &updates);
 
         Ok(updates)
     }
@@ -171,15 +648,21 @@ impl AccountExtractor for EVMAccountExtractor {
 mod tests {
     use std::str::FromStr;
 
+    use alloy::primitives::{Bytes as AlloyBytes, U256};
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
     use rstest::rstest;
+    use serde_json::json;
     use tracing::warn;
     use tracing_test::traced_test;
     use tycho_common::models::{Address, Chain};
 
     use super::*;
-    use crate::test_fixtures::{
-        TestFixture, BALANCER_VAULT_EXPECTED_SLOTS, BALANCER_VAULT_STR, STETH_EXPECTED_SLOTS,
-        STETH_STR, TEST_SLOTS, TOKEN_ADDRESSES,
+    use crate::{
+        rpc::config::RPCBatchingConfig,
+        test_fixtures::{
+            TestFixture, BALANCER_VAULT_EXPECTED_SLOTS, BALANCER_VAULT_STR, STETH_EXPECTED_SLOTS,
+            STETH_STR, TEST_SLOTS, TOKEN_ADDRESSES,
+        },
     };
 
     fn parse_address(address_str: &str) -> Address {
@@ -197,7 +680,7 @@ mod tests {
         fn create_evm_extractor(&self, batching: bool) -> EVMAccountExtractor {
             let rpc_client = self.create_rpc_client(batching);
 
-            EVMAccountExtractor { rpc: rpc_client, chain: Chain::Ethereum }
+            EVMAccountExtractor::new(&rpc_client, Chain::Ethereum)
         }
     }
 
@@ -252,6 +735,618 @@ mod tests {
         Ok(())
     }
 
+    #[rstest]
+    #[case::disjoint_slots(
+        vec![Some(vec![1, 2]), Some(vec![3])],
+        Some(vec![1, 2, 3])
+    )]
+    #[case::overlapping_slots(vec![Some(vec![1, 2]), Some(vec![2, 1])], Some(vec![1, 2]))]
+    #[case::full_storage_takes_precedence(vec![Some(vec![1]), None, Some(vec![2])], None)]
+    fn test_merge_requests(
+        #[case] requested_slots: Vec<Option<Vec<u8>>>,
+        #[case] expected_slots: Option<Vec<u8>>,
+    ) {
+        let slot = |value: u8| Bytes::from(B256::with_last_byte(value).to_vec());
+        let to_slots = |slots: Option<Vec<u8>>| -> Option<Vec<Bytes>> {
+            slots.map(|slots| slots.into_iter().map(slot).collect())
+        };
+        let mut requests: Vec<_> = requested_slots
+            .into_iter()
+            .map(|slots| create_storage_request(BALANCER_VAULT_STR, to_slots(slots)))
+            .collect();
+        requests.push(create_storage_request(STETH_STR, to_slots(Some(vec![9]))));
+
+        let mut merged = merge_requests(&requests);
+
+        merged.sort_by(|a, b| a.address.cmp(&b.address));
+        for request in &mut merged {
+            if let Some(slots) = &mut request.slots {
+                slots.sort();
+            }
+        }
+        let mut expected = vec![
+            create_storage_request(BALANCER_VAULT_STR, to_slots(expected_slots)),
+            create_storage_request(STETH_STR, to_slots(Some(vec![9]))),
+        ];
+        expected.sort_by(|a, b| a.address.cmp(&b.address));
+        assert_eq!(merged, expected);
+    }
+
+    #[tokio::test]
+    async fn test_get_accounts_at_block_explicit_slots_mocked() {
+        let mut server = mockito::Server::new_async().await;
+        let mock_method = |server: &mut mockito::ServerGuard, method: &str, param: &str| {
+            server
+                .mock("POST", "/")
+                .match_body(mockito::Matcher::AllOf(vec![
+                    mockito::Matcher::Regex(format!(r#""method":"{method}""#)),
+                    mockito::Matcher::Regex(param.to_string()),
+                ]))
+                .with_status(200)
+        };
+        let code_mock = mock_method(&mut server, "eth_getCode", "")
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x6001"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let balance_mock = mock_method(&mut server, "eth_getBalance", "")
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x1f4"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let nonce_mock = mock_method(&mut server, "eth_getTransactionCount", "")
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x3"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let slot_mock = mock_method(&mut server, "eth_getStorageAt", "0x0{63}1")
+            .with_body(format!(r#"{{"jsonrpc":"2.0","id":0,"result":"0x{:0>64}"}}"#, "5"))
+            .expect(1)
+            .create_async()
+            .await;
+        let zero_slot_mock = mock_method(&mut server, "eth_getStorageAt", "0x0{63}2")
+            .with_body(format!(r#"{{"jsonrpc":"2.0","id":0,"result":"0x{:0>64}"}}"#, "0"))
+            .expect(1)
+            .create_async()
+            .await;
+        let storage_range_mock = mock_method(&mut server, "debug_storageRangeAt", "")
+            .expect(0)
+            .create_async()
+            .await;
+        let client = EthereumRpcClient::new(&server.url())
+            .expect("create client")
+            .with_batching(RPCBatchingConfig::Disabled);
+        let extractor = EVMAccountExtractor::new(&client, Chain::Ethereum);
+        let slot = |value: u8| Bytes::from(B256::with_last_byte(value).to_vec());
+        let request = create_storage_request(BALANCER_VAULT_STR, Some(vec![slot(1), slot(2)]));
+        let block = Block { number: 1, hash: B256::ZERO.to_bytes(), ..Default::default() };
+
+        let updates = extractor
+            .get_accounts_at_block(&block, &[request])
+            .await
+            .expect("get accounts");
+
+        code_mock.assert();
+        balance_mock.assert();
+        nonce_mock.assert();
+        slot_mock.assert();
+        zero_slot_mock.assert();
+        storage_range_mock.assert();
+        let update = &updates[&parse_address(BALANCER_VAULT_STR)];
+        assert_eq!(update.slots, HashMap::from([(slot(1), Some(slot(5))), (slot(2), None)]));
+        assert_eq!(update.code(), &Some(Bytes::from("0x6001")));
+        assert_eq!(update.nonce, Some(3));
+    }
+
+    #[tokio::test]
+    async fn test_get_accounts_at_block_proof_strategy() {
+        let mut server = mockito::Server::new_async().await;
+        let slot = |value: u8| Bytes::from(B256::with_last_byte(value).to_vec());
+        let proof_response = json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "result": {
+                "address": BALANCER_VAULT_STR,
+                "balance": "0x1f4",
+                "codeHash": format!("0x{:0>64}", "c0de"),
+                "nonce": "0x1",
+                "storageHash": format!("0x{:0>64}", "5707"),
+                "accountProof": ["0xf8"],
+                "storageProof": [
+                    { "key": format!("0x{:0>64}", "1"), "value": "0x5", "proof": ["0xe2"] },
+                    { "key": format!("0x{:0>64}", "2"), "value": "0x0", "proof": [] }
+                ]
+            }
+        });
+        let proof_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(r#""method":"eth_getProof""#.to_string()),
+                mockito::Matcher::Regex("0x0{63}1".to_string()),
+                mockito::Matcher::Regex("0x0{63}2".to_string()),
+            ]))
+            .with_body(proof_response.to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let code_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""method":"eth_getCode""#.to_string()))
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x6001"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let other_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(
+                r#""method":"(eth_getBalance|eth_getStorageAt|debug_storageRangeAt)""#.to_string(),
+            ))
+            .expect(0)
+            .create_async()
+            .await;
+        let client = EthereumRpcClient::new(&server.url()).expect("create client");
+        let extractor = EVMAccountExtractor::new(&client, Chain::Ethereum)
+            .with_storage_strategy(StorageExtractionStrategy::Proof);
+        let request = create_storage_request(BALANCER_VAULT_STR, Some(vec![slot(1), slot(2)]));
+        let block = Block { number: 1, hash: B256::ZERO.to_bytes(), ..Default::default() };
+
+        let accounts = extractor
+            .get_accounts_and_proofs_at_block(&block, &[request])
+            .await
+            .expect("get accounts");
+
+        proof_mock.assert();
+        code_mock.assert();
+        other_mock.assert();
+        let (update, proof) = &accounts[&parse_address(BALANCER_VAULT_STR)];
+        assert_eq!(update.slots, HashMap::from([(slot(1), Some(slot(5))), (slot(2), None)]));
+        assert_eq!(update.code(), &Some(Bytes::from("0x6001")));
+        assert_eq!(update.balance, Some(U256::from(500).to_bytes()));
+        assert_eq!(update.nonce, Some(1));
+        assert_eq!(proof.account_proof, vec![AlloyBytes::from_static(&[0xf8])]);
+        assert_eq!(proof.storage_proof.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_accounts_at_block_proof_strategy_eoa() {
+        let mut server = mockito::Server::new_async().await;
+        let proof_response = json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "result": {
+                "address": BALANCER_VAULT_STR,
+                "balance": "0x1f4",
+                "codeHash": KECCAK256_EMPTY,
+                "nonce": "0x0",
+                "storageHash": format!("0x{:0>64}", "0"),
+                "accountProof": [],
+                "storageProof": []
+            }
+        });
+        let proof_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""method":"eth_getProof""#.to_string()))
+            .with_body(proof_response.to_string())
+            .expect(1)
+            .create_async()
+            .await;
+        let code_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""method":"eth_getCode""#.to_string()))
+            .expect(0)
+            .create_async()
+            .await;
+        let client = EthereumRpcClient::new(&server.url()).expect("create client");
+        let extractor = EVMAccountExtractor::new(&client, Chain::Ethereum)
+            .with_storage_strategy(StorageExtractionStrategy::Proof);
+        let request = create_storage_request(BALANCER_VAULT_STR, Some(vec![]));
+        let block = Block { number: 1, hash: B256::ZERO.to_bytes(), ..Default::default() };
+
+        let updates = extractor
+            .get_accounts_at_block(&block, &[request])
+            .await
+            .expect("get accounts");
+
+        proof_mock.assert();
+        code_mock.assert();
+        let update = &updates[&parse_address(BALANCER_VAULT_STR)];
+        assert!(update.slots.is_empty());
+        assert_eq!(update.code(), &Some(Bytes::new()));
+        assert_eq!(update.balance, Some(U256::from(500).to_bytes()));
+    }
+
+    fn block_response(number: u64, hash: B256) -> String {
+        json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "result": {
+                "number": format!("{number:#x}"),
+                "hash": hash,
+                "parentHash": B256::ZERO,
+                "sha3Uncles": B256::ZERO,
+                "miner": AlloyAddress::ZERO,
+                "stateRoot": B256::ZERO,
+                "transactionsRoot": B256::ZERO,
+                "receiptsRoot": B256::ZERO,
+                "logsBloom": format!("0x{:0>512}", ""),
+                "difficulty": "0x0",
+                "timestamp": "0x64",
+                "gasLimit": "0x0",
+                "gasUsed": "0x0",
+                "extraData": "0x",
+                "mixHash": B256::ZERO,
+                "nonce": "0x0000000000000000",
+                "size": "0x0",
+                "transactions": []
+            }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_get_block_data() {
+        let mut server = mockito::Server::new_async().await;
+        let hash = B256::with_last_byte(0xaa);
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""params":\["0x10""#.to_string()))
+            .with_body(block_response(16, hash))
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""params":\["0x11""#.to_string()))
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":null}"#)
+            .create_async()
+            .await;
+        let client = EthereumRpcClient::new(&server.url()).expect("create client");
+        let extractor = EVMAccountExtractor::new(&client, Chain::Arbitrum);
+
+        let block = extractor
+            .get_block_data(16)
+            .await
+            .expect("get block");
+        let missing = extractor.get_block_data(17).await;
+
+        assert_eq!(block.number, 16);
+        assert_eq!(block.hash, hash.to_bytes());
+        assert_eq!(block.chain, Chain::Arbitrum);
+        assert!(matches!(missing, Err(RPCError::RequestError(RequestError::Other(_)))));
+    }
+
+    #[tokio::test]
+    async fn test_get_block_data_by_tag() {
+        let mut server = mockito::Server::new_async().await;
+        let hash = B256::with_last_byte(0xaa);
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""params":\["finalized""#.to_string()))
+            .with_body(block_response(16, hash))
+            .create_async()
+            .await;
+        let client = EthereumRpcClient::new(&server.url()).expect("create client");
+        let extractor = EVMAccountExtractor::new(&client, Chain::Ethereum);
+
+        let block = extractor
+            .get_block_data(BlockNumberOrTag::Finalized)
+            .await
+            .expect("get block");
+
+        assert_eq!(block.number, 16);
+        assert_eq!(block.hash, hash.to_bytes());
+        assert_eq!(block.parent_hash, B256::ZERO.to_bytes());
+        assert_eq!(block.ts.and_utc().timestamp(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_get_accounts_at_block_id() {
+        let mut server = mockito::Server::new_async().await;
+        let resolved_hash = B256::with_last_byte(0xaa);
+        let finalized_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(
+                r#""method":"eth_getBlockByNumber","params":\["finalized""#.to_string(),
+            ))
+            .with_body(block_response(16, resolved_hash))
+            .expect(1)
+            .create_async()
+            .await;
+        let account_mocks = [
+            ("eth_getCode", "0x6001"),
+            ("eth_getBalance", "0x1f4"),
+            ("eth_getTransactionCount", "0x1"),
+            ("eth_getStorageAt", &format!("0x{:0>64}", "5")),
+        ];
+        let mut mocks = Vec::new();
+        for (method, result) in account_mocks {
+            mocks.push(
+                server
+                    .mock("POST", "/")
+                    .match_body(mockito::Matcher::AllOf(vec![
+                        mockito::Matcher::Regex(format!(r#""method":"{method}""#)),
+                        // All values must be read at the resolved block hash.
+                        mockito::Matcher::Regex(format!(r#""blockHash":"{resolved_hash}""#)),
+                    ]))
+                    .with_body(format!(r#"{{"jsonrpc":"2.0","id":0,"result":"{result}"}}"#))
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
+        let client = EthereumRpcClient::new(&server.url())
+            .expect("create client")
+            .with_batching(RPCBatchingConfig::Disabled);
+        let extractor = EVMAccountExtractor::new(&client, Chain::Ethereum);
+        let slot = Bytes::from(B256::with_last_byte(1).to_vec());
+        let request = create_storage_request(BALANCER_VAULT_STR, Some(vec![slot]));
+
+        let (block, updates) = extractor
+            .get_accounts_at_block_id(BlockNumberOrTag::Finalized, &[request])
+            .await
+            .expect("get accounts");
+
+        finalized_mock.assert();
+        for mock in mocks {
+            mock.assert();
+        }
+        assert_eq!(block.number, 16);
+        assert_eq!(block.hash, resolved_hash.to_bytes());
+        assert!(updates.contains_key(&parse_address(BALANCER_VAULT_STR)));
+    }
+
+    #[tokio::test]
+    async fn test_get_accounts_at_block_code_cache() {
+        let mut server = mockito::Server::new_async().await;
+        let code_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""method":"eth_getCode""#.to_string()))
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x6001"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let balance_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""method":"eth_getBalance""#.to_string()))
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x1f4"}"#)
+            .expect(3)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(
+                r#""method":"eth_getTransactionCount""#.to_string(),
+            ))
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x1"}"#)
+            .create_async()
+            .await;
+        let client = EthereumRpcClient::new(&server.url())
+            .expect("create client")
+            .with_batching(RPCBatchingConfig::Disabled);
+        let extractor = EVMAccountExtractor::new(&client, Chain::Ethereum).with_code_cache_size(10);
+        let request = create_storage_request(BALANCER_VAULT_STR, Some(vec![]));
+
+        // The code is cached at block 2 and reused for block 3, but fetched again for block 1 as
+        // the contract may not have been deployed yet.
+        for number in [2, 3, 1] {
+            let block = Block { number, hash: B256::ZERO.to_bytes(), ..Default::default() };
+            let updates = extractor
+                .get_accounts_at_block(&block, std::slice::from_ref(&request))
+                .await
+                .expect("get accounts");
+
+            assert_eq!(
+                updates[&parse_address(BALANCER_VAULT_STR)].code(),
+                &Some(Bytes::from("0x6001"))
+            );
+        }
+
+        code_mock.assert();
+        balance_mock.assert();
+    }
+
+    #[test]
+    fn test_get_accounts_at_block_records_metrics() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("build runtime");
+
+        metrics::with_local_recorder(&recorder, || {
+            runtime.block_on(async {
+                let mut server = mockito::Server::new_async().await;
+                for (method, result) in [
+                    ("eth_getCode", "0x6001".to_string()),
+                    ("eth_getBalance", "0x1f4".to_string()),
+                    ("eth_getTransactionCount", "0x1".to_string()),
+                    ("eth_getStorageAt", format!("0x{:0>64}", "5")),
+                ] {
+                    server
+                        .mock("POST", "/")
+                        .match_body(mockito::Matcher::Regex(format!(r#""method":"{method}""#)))
+                        .with_body(format!(r#"{{"jsonrpc":"2.0","id":0,"result":"{result}"}}"#))
+                        .create_async()
+                        .await;
+                }
+                let client = EthereumRpcClient::new(&server.url())
+                    .expect("create client")
+                    .with_batching(RPCBatchingConfig::Disabled);
+                let extractor = EVMAccountExtractor::new(&client, Chain::Ethereum);
+                let slot = |value: u8| Bytes::from(B256::with_last_byte(value).to_vec());
+                let request =
+                    create_storage_request(BALANCER_VAULT_STR, Some(vec![slot(1), slot(2)]));
+                let block = Block { number: 1, hash: B256::ZERO.to_bytes(), ..Default::default() };
+
+                extractor
+                    .get_accounts_at_block(&block, &[request])
+                    .await
+                    .expect("get accounts");
+            })
+        });
+
+        let counters: HashMap<_, _> = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .filter_map(|(key, _, _, value)| match value {
+                DebugValue::Counter(count) => Some((key.key().name().to_string(), count)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(counters["account_extractor_accounts_fetched"], 1);
+        assert_eq!(counters["account_extractor_slots_fetched"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_accounts_at_block_max_concurrency() {
+        let mut server = mockito::Server::new_async().await;
+        let code_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""method":"eth_getCode""#.to_string()))
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x6001"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        let balance_mock = server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""method":"eth_getBalance""#.to_string()))
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x1f4"}"#)
+            .expect(2)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(
+                r#""method":"eth_getTransactionCount""#.to_string(),
+            ))
+            .with_body(
+                r#"{"jsonrpc":"2.0","id":0,"error":{"code":-32601,"message":"method not found"}}"#,
+            )
+            .create_async()
+            .await;
+        let mut slot_mocks = Vec::new();
+        for (slot, value) in [(1, 5), (3, 7)] {
+            slot_mocks.push(
+                server
+                    .mock("POST", "/")
+                    .match_body(mockito::Matcher::AllOf(vec![
+                        mockito::Matcher::Regex(r#""method":"eth_getStorageAt""#.to_string()),
+                        mockito::Matcher::Regex(format!("0x0{{63}}{slot}")),
+                    ]))
+                    .with_body(format!(r#"{{"jsonrpc":"2.0","id":0,"result":"0x{value:0>64}"}}"#))
+                    .expect(1)
+                    .create_async()
+                    .await,
+            );
+        }
+        let client = EthereumRpcClient::new(&server.url())
+            .expect("create client")
+            .with_batching(RPCBatchingConfig::Disabled);
+        let extractor = EVMAccountExtractor::new(&client, Chain::Ethereum).with_max_concurrency(1);
+        let slot = |value: u8| Bytes::from(B256::with_last_byte(value).to_vec());
+        let requests = [
+            create_storage_request(BALANCER_VAULT_STR, Some(vec![slot(1)])),
+            create_storage_request(STETH_STR, Some(vec![slot(3)])),
+        ];
+        let block = Block { number: 1, hash: B256::ZERO.to_bytes(), ..Default::default() };
+
+        let updates = extractor
+            .get_accounts_at_block(&block, &requests)
+            .await
+            .expect("get accounts");
+
+        code_mock.assert();
+        balance_mock.assert();
+        for mock in slot_mocks {
+            mock.assert();
+        }
+        assert_eq!(
+            updates[&parse_address(BALANCER_VAULT_STR)].slots,
+            HashMap::from([(slot(1), Some(slot(5)))])
+        );
+        assert_eq!(
+            updates[&parse_address(STETH_STR)].slots,
+            HashMap::from([(slot(3), Some(slot(7)))])
+        );
+        // A provider without eth_getTransactionCount does not fail the extraction.
+        assert_eq!(updates[&parse_address(STETH_STR)].nonce, None);
+    }
+
+    #[tokio::test]
+    async fn test_get_accounts_at_block_partial() {
+        let mut server = mockito::Server::new_async().await;
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""method":"eth_getCode""#.to_string()))
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x6001"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(r#""method":"eth_getBalance""#.to_string()))
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x1f4"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::Regex(
+                r#""method":"eth_getTransactionCount""#.to_string(),
+            ))
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x1"}"#)
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(r#""method":"eth_getStorageAt""#.to_string()),
+                mockito::Matcher::Regex("0x0{63}1".to_string()),
+            ]))
+            .with_body(format!(r#"{{"jsonrpc":"2.0","id":0,"result":"0x{:0>64}"}}"#, 5))
+            .create_async()
+            .await;
+        server
+            .mock("POST", "/")
+            .match_body(mockito::Matcher::AllOf(vec![
+                mockito::Matcher::Regex(r#""method":"eth_getStorageAt""#.to_string()),
+                mockito::Matcher::Regex("0x0{63}3".to_string()),
+            ]))
+            .with_body(
+                r#"{"jsonrpc":"2.0","id":0,"error":{"code":-32602,"message":"invalid params"}}"#,
+            )
+            .create_async()
+            .await;
+        let client = EthereumRpcClient::new(&server.url())
+            .expect("create client")
+            .with_batching(RPCBatchingConfig::Disabled);
+        let extractor = EVMAccountExtractor::new(&client, Chain::Ethereum);
+        let slot = |value: u8| Bytes::from(B256::with_last_byte(value).to_vec());
+        let requests = [
+            create_storage_request(BALANCER_VAULT_STR, Some(vec![slot(1)])),
+            create_storage_request(STETH_STR, Some(vec![slot(3)])),
+        ];
+        let block = Block { number: 1, hash: B256::ZERO.to_bytes(), ..Default::default() };
+
+        assert!(extractor
+            .get_accounts_at_block(&block, &requests)
+            .await
+            .is_err());
+
+        let accounts = extractor
+            .get_accounts_at_block_partial(&block, &requests)
+            .await;
+
+        assert_eq!(accounts.succeeded.len(), 1);
+        assert_eq!(
+            accounts.succeeded[&parse_address(BALANCER_VAULT_STR)].slots,
+            HashMap::from([(slot(1), Some(slot(5)))])
+        );
+        assert_eq!(accounts.failed.len(), 1);
+        assert!(accounts
+            .failed
+            .contains_key(&parse_address(STETH_STR)));
+    }
+
     #[rstest]
     #[traced_test]
     #[tokio::test]
@@ -1,4 +1,5 @@
 pub mod account_extractor;
 pub mod entrypoint_tracer;
+pub mod starknet_account_extractor;
 pub mod token_analyzer;
 pub mod token_pre_processor;
@@ -0,0 +1,245 @@
+use std::{collections::HashMap, str::FromStr};
+
+use async_trait::async_trait;
+use futures::future::try_join_all;
+use serde_json::{json, Value};
+use tracing::{debug, info};
+use tycho_common::{
+    models::{blockchain::Block, contract::AccountDelta, Chain, ChangeType},
+    traits::{AccountExtractor, StorageSnapshotRequest},
+    Bytes,
+};
+
+use crate::{
+    rpc::{
+        errors::{RPCError, RequestError},
+        EthereumRpcClient,
+    },
+    services::account_extractor::merge_requests,
+};
+
+/// `StarknetAccountExtractor` implements the `AccountExtractor` trait for Starknet contracts
+/// using the Starknet JSON-RPC API.
+///
+/// Starknet contracts do not hold their code directly but reference a declared class, so the
+/// class hash is returned as the account's code. Native balances are tracked by the fee token
+/// contracts, so no balance is returned. Starknet nodes do not support iterating over a
+/// contract's storage, hence only requests with explicit slots are supported.
+pub struct StarknetAccountExtractor {
+    rpc: EthereumRpcClient,
+}
+
+impl StarknetAccountExtractor {
+    pub fn new(client: &EthereumRpcClient) -> Self {
+        // As the client is a thin wrapper around an Arc, cloning is inexpensive.
+        Self { rpc: client.clone() }
+    }
+
+    /// Fetches the class hash and the requested storage slots of a single contract.
+    async fn get_account(
+        &self,
+        block_id: &Value,
+        request: &StorageSnapshotRequest,
+    ) -> Result<AccountDelta, RPCError> {
+        let address = to_felt(&request.address);
+        let slots = request.slots.as_ref().ok_or_else(|| {
+            RPCError::RequestError(RequestError::Other(format!(
+                "Failed to get storage for address {address}: Starknet nodes do not support full \
+                 storage retrieval, explicit slots are required"
+            )))
+        })?;
+
+        let class_hash = self
+            .rpc
+            .starknet_get_class_hash_at(block_id, &address)
+            .await?;
+
+        let mut storage = HashMap::with_capacity(slots.len());
+        for slot in slots {
+            let value = self
+                .rpc
+                .starknet_get_storage_at(block_id, &address, &to_felt(slot))
+                .await?;
+            let value = from_felt(&value)?;
+            let value = if value.iter().all(|b| *b == 0) { None } else { Some(value) };
+            storage.insert(slot.clone(), value);
+        }
+
+        Ok(AccountDelta::new(
+            Chain::Starknet,
+            request.address.clone(),
+            storage,
+            None,
+            Some(from_felt(&class_hash)?),
+            ChangeType::Creation,
+        ))
+    }
+}
+
+/// Encodes bytes as a Starknet field element: hex without leading zeros.
+fn to_felt(bytes: &Bytes) -> String {
+    let hex = bytes.to_string();
+    let digits = hex
+        .trim_start_matches("0x")
+        .trim_start_matches('0');
+    if digits.is_empty() {
+        "0x0".to_string()
+    } else {
+        format!("0x{digits}")
+    }
+}
+
+/// Decodes a Starknet field element into 32 bytes.
+fn from_felt(felt: &str) -> Result<Bytes, RPCError> {
+    let digits = felt.trim_start_matches("0x");
+    if digits.len() > 64 {
+        return Err(RPCError::RequestError(RequestError::Other(format!(
+            "Invalid field element {felt}: longer than 32 bytes"
+        ))));
+    }
+    Bytes::from_str(&format!("{digits:0>64}")).map_err(|e| {
+        RPCError::RequestError(RequestError::Other(format!("Invalid field element {felt}: {e}")))
+    })
+}
+
+#[async_trait]
+impl AccountExtractor for StarknetAccountExtractor {
+    type Error = RPCError;
+
+    async fn get_accounts_at_block(
+        &self,
+        block: &Block,
+        requests: &[StorageSnapshotRequest],
+    ) -> Result<HashMap<Bytes, AccountDelta>, Self::Error> {
+        let block_id = json!({ "block_number": block.number });
+
+        // Merge requests for the same contract to avoid making more requests than necessary.
+        let unique_requests = merge_requests(requests);
+
+        let accounts = try_join_all(
+            unique_requests
+                .iter()
+                .map(|request| self.get_account(&block_id, request)),
+        )
+        .await?;
+        debug!(block_number = block.number, "Successfully retrieved account storage");
+
+        let updates: HashMap<_, _> = accounts
+            .into_iter()
+            .map(|account| (account.address.clone(), account))
+            .collect();
+
+        info!(
+            total_accounts_processed = updates.len(),
+            block_number = block.number,
+            "Completed account extraction successfully"
+        );
+
+        Ok(updates)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDateTime;
+    use mockito::{Matcher, Server};
+
+    use super::*;
+
+    fn block() -> Block {
+        Block {
+            number: 1000,
+            hash: Bytes::from("0x01"),
+            parent_hash: Bytes::from("0x00"),
+            chain: Chain::Starknet,
+            ts: NaiveDateTime::default(),
+        }
+    }
+
+    #[test]
+    fn test_felt_conversion() {
+        assert_eq!(to_felt(&Bytes::from("0x000000abc0")), "0xabc0");
+        assert_eq!(to_felt(&Bytes::from("0x0000")), "0x0");
+        assert_eq!(from_felt("0xabc").unwrap(), Bytes::from(format!("0x{:0>64}", "0abc").as_str()));
+        assert!(from_felt(&format!("0x1{:0>64}", "")).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_get_accounts_at_block() {
+        let mut server = Server::new_async().await;
+        let class_hash_mock = server
+            .mock("POST", "/")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(r#""method":"starknet_getClassHashAt""#.to_string()),
+                Matcher::Regex(r#""params":\[\{"block_number":1000\},"0x4aa""#.to_string()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x1234"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let storage_mock = server
+            .mock("POST", "/")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(r#""method":"starknet_getStorageAt""#.to_string()),
+                Matcher::Regex(r#""params":\["0x4aa","0x1",\{"block_number":1000\}\]"#.to_string()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x5"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let zero_storage_mock = server
+            .mock("POST", "/")
+            .match_body(Matcher::AllOf(vec![
+                Matcher::Regex(r#""method":"starknet_getStorageAt""#.to_string()),
+                Matcher::Regex(r#""params":\["0x4aa","0x2",\{"block_number":1000\}\]"#.to_string()),
+            ]))
+            .with_status(200)
+            .with_body(r#"{"jsonrpc":"2.0","id":0,"result":"0x0"}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let client = EthereumRpcClient::new(&server.url()).expect("create client");
+        let extractor = StarknetAccountExtractor::new(&client);
+        let address = Bytes::from("0x04aa");
+        let request = StorageSnapshotRequest {
+            address: address.clone(),
+            slots: Some(vec![Bytes::from("0x01"), Bytes::from("0x02")]),
+        };
+
+        let updates = extractor
+            .get_accounts_at_block(&block(), &[request.clone(), request])
+            .await
+            .expect("get accounts");
+
+        class_hash_mock.assert();
+        storage_mock.assert();
+        zero_storage_mock.assert();
+        let expected = AccountDelta::new(
+            Chain::Starknet,
+            address.clone(),
+            HashMap::from([
+                (Bytes::from("0x01"), Some(from_felt("0x5").unwrap())),
+                (Bytes::from("0x02"), None),
+            ]),
+            None,
+            Some(from_felt("0x1234").unwrap()),
+            ChangeType::Creation,
+        );
+        assert_eq!(updates, HashMap::from([(address, expected)]));
+    }
+
+    #[tokio::test]
+    async fn test_get_accounts_at_block_requires_slots() {
+        let client = EthereumRpcClient::new("http://localhost:1").expect("create client");
+        let extractor = StarknetAccountExtractor::new(&client);
+        let request = StorageSnapshotRequest { address: Bytes::from("0x04aa"), slots: None };
+
+        let result = extractor
+            .get_accounts_at_block(&block(), &[request])
+            .await;
+
+        assert!(matches!(result, Err(RPCError::RequestError(RequestError::Other(_)))));
+    }
+}
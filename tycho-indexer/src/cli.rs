@@ -126,6 +126,20 @@ pub struct ServerArgs {
         alias = "rpc-min-traded-n-days-ago" // to ensure backward compatibility, TODO: remove after next prod release
     )]
     pub max_traded_n_days_ago: Option<u64>,
+
+    /// Require contract ids on contract state requests
+    /// Contract state requests without contract ids will be rejected
+    #[clap(
+        long = "rpc-require-contract-ids",
+        env = "RPC_REQUIRE_CONTRACT_IDS",
+        default_value = "false"
+    )]
+    pub require_contract_ids: bool,
+
+    /// Maximum number of accounts returned per contract state request
+    /// Contract state requests for more accounts will be rejected
+    #[clap(long = "rpc-max-contract-accounts", env = "RPC_MAX_CONTRACT_ACCOUNTS")]
+    pub max_contract_accounts: Option<usize>,
 }
 
 impl From<ServerArgs> for ServerRpcConfig {
@@ -134,6 +148,8 @@ impl From<ServerArgs> for ServerRpcConfig {
             .with_min_tvl(args.min_tvl)
             .with_min_quality(args.min_token_quality)
             .with_max_traded_n_days_ago(args.max_traded_n_days_ago)
+            .with_require_contract_ids(args.require_contract_ids)
+            .with_max_contract_accounts(args.max_contract_accounts)
     }
 }
 
@@ -318,6 +334,8 @@ mod cli_tests {
                     min_tvl: None,
                     min_token_quality: None,
                     max_traded_n_days_ago: None,
+                    require_contract_ids: false,
+                    max_contract_accounts: None,
                 },
             },
             command: Command::Run(RunSpkgArgs {
@@ -385,6 +403,8 @@ mod cli_tests {
                     min_tvl: None,
                     min_token_quality: None,
                     max_traded_n_days_ago: None,
+                    require_contract_ids: false,
+                    max_contract_accounts: None,
                 },
             },
             command: Command::Index(IndexArgs {
@@ -1303,14 +1303,7 @@ where
                                 // tracked_keys
                                 for (slot, change) in contract_changes.slots.iter() {
                                     if tracked_keys.contains(slot) {
-                                        result.insert(
-                                            slot.clone(),
-                                            if change.value.is_zero() {
-                                                None
-                                            } else {
-                                                Some(change.value.clone())
-                                            },
-                                        );
+                                        result.insert(slot.clone(), change.updated_value());
                                     }
                                 }
                             } else {
@@ -1318,14 +1311,7 @@ where
                                 // slots
                                 for slot in tracked_keys.iter() {
                                     if let Some(change) = contract_changes.slots.get(slot) {
-                                        result.insert(
-                                            slot.clone(),
-                                            if change.value.is_zero() {
-                                                None
-                                            } else {
-                                                Some(change.value.clone())
-                                            },
-                                        );
+                                        result.insert(slot.clone(), change.updated_value());
                                     }
                                 }
                             }
@@ -1336,12 +1322,7 @@ where
                         contract_changes
                             .slots
                             .iter()
-                            .map(|(slot, ContractStorageChange { value, .. })| {
-                                (
-                                    slot.clone(),
-                                    if value.is_zero() { None } else { Some(value.clone()) },
-                                )
-                            })
+                            .map(|(slot, change)| (slot.clone(), change.updated_value()))
                             .collect()
                     }
                 };
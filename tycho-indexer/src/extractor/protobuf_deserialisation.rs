@@ -1,8 +1,11 @@
-#![allow(deprecated)]
-use std::collections::{hash_map::Entry, HashMap, HashSet};
+use std::{
+    collections::{hash_map::Entry, HashMap, HashSet},
+    sync::OnceLock,
+};
 
 use chrono::{DateTime, NaiveDateTime};
-use tracing::warn;
+use metrics::counter;
+use tracing::{trace, warn};
 use tycho_common::{
     models::{
         blockchain::{
@@ -21,12 +24,75 @@ use tycho_common::{
 };
 use tycho_substreams::pb::tycho::evm::v1 as substreams;
 
+#[allow(deprecated)]
 use crate::extractor::{
     models::{BlockChanges, BlockContractChanges, BlockEntityChanges, TxWithContractChanges},
     u256_num::bytes_to_f64,
     ExtractionError,
 };
 
+const HASH_LENGTH: usize = 32;
+/// Unix timestamp in seconds of 3000-01-01T00:00:00Z.
+const MAX_BLOCK_TIMESTAMP: u64 = 32_503_680_000;
+
+/// Returns the expected byte length of an address on the given chain.
+fn address_length(chain: Chain) -> usize {
+    match chain {
+        Chain::Starknet => 32,
+        Chain::Ethereum |
+        Chain::ZkSync |
+        Chain::Arbitrum |
+        Chain::Base |
+        Chain::Bsc |
+        Chain::Unichain => 20,
+    }
+}
+
+/// Converts a decoded field to `Bytes`, failing if it does not have the expected length.
+fn checked_bytes(field: &str, value: Vec<u8>, expected: usize) -> Result<Bytes, ExtractionError> {
+    if value.len() != expected {
+        return Err(ExtractionError::DecodeError(format!(
+            "Invalid {field} length: expected {expected} bytes, got {}",
+            value.len()
+        )));
+    }
+    Ok(value.into())
+}
+
+/// If the `STRICT_BALANCE_DECODING` env var is set to `true`, component balances that can't be
+/// converted to a float are rejected instead of being stored as NaN.
+fn strict_balance_decoding() -> bool {
+    static STRICT: OnceLock<bool> = OnceLock::new();
+    *STRICT.get_or_init(|| {
+        std::env::var("STRICT_BALANCE_DECODING")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(false)
+    })
+}
+
+/// Converts a component balance to a float.
+///
+/// Failed conversions are logged and counted. They return a `DecodeError` in strict mode and NaN
+/// otherwise.
+fn balance_to_f64(
+    component_id: &str,
+    balance: &Bytes,
+    strict: bool,
+) -> Result<f64, ExtractionError> {
+    if let Some(value) = bytes_to_f64(balance) {
+        return Ok(value);
+    }
+    warn!(%component_id, %balance, "Failed to convert component balance to float.");
+    counter!("extractor_invalid_component_balances").increment(1);
+    if strict {
+        return Err(ExtractionError::DecodeError(format!(
+            "Failed to convert balance {balance} of component {component_id} to float"
+        )));
+    }
+    Ok(f64::NAN)
+}
+
 pub trait TryFromMessage {
     type Args<'a>;
 
@@ -43,11 +109,21 @@ impl TryFromMessage for AccountDelta {
         let change = ChangeType::try_from_message(msg.change())?;
         let update = AccountDelta::new(
             chain,
-            msg.address.into(),
+            checked_bytes("address", msg.address, address_length(chain))?,
             msg.slots
                 .into_iter()
-                .map(|cs| (cs.slot.into(), Some(cs.value.into())))
-                .collect(),
+                .map(|cs| {
+                    // Slots are not run through `checked_bytes`: substreams packages may emit
+                    // them with leading zero bytes stripped, so only the upper bound is checked.
+                    if cs.slot.len() > HASH_LENGTH {
+                        return Err(ExtractionError::DecodeError(format!(
+                            "Invalid slot length: expected at most {HASH_LENGTH} bytes, got {}",
+                            cs.slot.len()
+                        )));
+                    }
+                    Ok((cs.slot.into(), Some(cs.value.into())))
+                })
+                .collect::<Result<_, _>>()?,
             if !msg.balance.is_empty() { Some(msg.balance.into()) } else { None },
             if !msg.code.is_empty() { Some(msg.code.into()) } else { None },
             change,
@@ -77,11 +153,20 @@ impl TryFromMessage for Block {
     fn try_from_message(args: Self::Args<'_>) -> Result<Self, ExtractionError> {
         let (msg, chain) = args;
 
+        // Block timestamps are expected in seconds, a larger value most likely means the
+        // substream emitted a different unit.
+        if msg.ts > MAX_BLOCK_TIMESTAMP {
+            return Err(ExtractionError::DecodeError(format!(
+                "Implausible block timestamp {}, expected seconds before year 3000",
+                msg.ts
+            )));
+        }
+
         Ok(Self {
             chain,
             number: msg.number,
-            hash: msg.hash.into(),
-            parent_hash: msg.parent_hash.into(),
+            hash: checked_bytes("block hash", msg.hash, HASH_LENGTH)?,
+            parent_hash: checked_bytes("parent hash", msg.parent_hash, HASH_LENGTH)?,
             ts: DateTime::from_timestamp(msg.ts as i64, 0)
                 .ok_or_else(|| {
                     ExtractionError::DecodeError(format!(
@@ -95,17 +180,21 @@ impl TryFromMessage for Block {
 }
 
 impl TryFromMessage for Transaction {
-    type Args<'a> = (substreams::Transaction, &'a TxHash);
+    type Args<'a> = (substreams::Transaction, &'a TxHash, Chain);
 
     fn try_from_message(args: Self::Args<'_>) -> Result<Self, ExtractionError> {
-        let (msg, block_hash) = args;
+        let (msg, block_hash, chain) = args;
 
-        let to = if !msg.to.is_empty() { Some(msg.to.into()) } else { None };
+        let to = if !msg.to.is_empty() {
+            Some(checked_bytes("to address", msg.to, address_length(chain))?)
+        } else {
+            None
+        };
 
         Ok(Self {
-            hash: msg.hash.into(),
+            hash: checked_bytes("transaction hash", msg.hash, HASH_LENGTH)?,
             block_hash: block_hash.clone(),
-            from: msg.from.into(),
+            from: checked_bytes("from address", msg.from, address_length(chain))?,
             to,
             index: msg.index,
         })
@@ -117,14 +206,16 @@ impl TryFromMessage for ComponentBalance {
 
     fn try_from_message(args: Self::Args<'_>) -> Result<Self, ExtractionError> {
         let (msg, tx) = args;
-        let balance_float = bytes_to_f64(&msg.balance).unwrap_or(f64::NAN);
+        let component_id = String::from_utf8(msg.component_id)
+            .map_err(|error| ExtractionError::DecodeError(error.to_string()))?;
+        let balance = Bytes::from(msg.balance);
+        let balance_float = balance_to_f64(&component_id, &balance, strict_balance_decoding())?;
         Ok(Self {
             token: msg.token.into(),
-            balance: Bytes::from(msg.balance),
+            balance,
             balance_float,
             modify_tx: tx.hash.clone(),
-            component_id: String::from_utf8(msg.component_id)
-                .map_err(|error| ExtractionError::DecodeError(error.to_string()))?,
+            component_id,
         })
     }
 }
@@ -270,9 +361,13 @@ impl TryFromMessage for ProtocolChangesWithTx {
     fn try_from_message(args: Self::Args<'_>) -> Result<Self, ExtractionError> {
         let (msg, block, protocol_system, protocol_types) = args;
         let tx = Transaction::try_from_message((
-            msg.tx
-                .expect("TransactionEntityChanges should have a transaction"),
+            msg.tx.ok_or_else(|| {
+                ExtractionError::DecodeError(
+                    "TransactionEntityChanges should have a transaction".to_owned(),
+                )
+            })?,
             &block.hash.clone(),
+            block.chain,
         ))?;
 
         let mut new_protocol_components: HashMap<String, ProtocolComponent> = HashMap::new();
@@ -296,15 +391,14 @@ impl TryFromMessage for ProtocolChangesWithTx {
         // Then, parse the state updates
         for state_msg in msg.entity_changes.into_iter() {
             let state = ProtocolComponentStateDelta::try_from_message(state_msg)?;
-            // Check if a state update for the same component already exists
-            // If it exists, overwrite the existing state update with the new one and log a warning
+            // If a state update for the same component already exists, merge the new one into it.
+            // Attributes updated by both keep the value of the later update.
             match state_updates.entry(state.component_id.clone()) {
                 Entry::Vacant(e) => {
                     e.insert(state);
                 }
                 Entry::Occupied(mut e) => {
-                    warn!("Received two state updates for the same component. Overwriting state for component {}", e.key());
-                    e.insert(state);
+                    e.get_mut().merge(state)?;
                 }
             }
         }
@@ -313,20 +407,32 @@ impl TryFromMessage for ProtocolChangesWithTx {
         for balance_change in msg.balance_changes.into_iter() {
             let component_balance = ComponentBalance::try_from_message((balance_change, &tx))?;
 
-            // Check if a balance change for the same token and component already exists
-            // If it exists, overwrite the existing balance change with the new one and log a
-            // warning
+            // If a balance change for the same token and component already exists, the later one
+            // wins as it reflects the balance at the end of the transaction. Identical duplicates
+            // are a common no-op from duplicated substreams output, so they are only traced.
             let token_balances = component_balances
                 .entry(component_balance.component_id.clone())
                 .or_default();
 
+            let new_balance = component_balance.balance.clone();
             if let Some(existing_balance) =
                 token_balances.insert(component_balance.token.clone(), component_balance)
             {
-                warn!(
-                    "Received two balance updates for the same component id: {} and token {}. Overwriting balance change",
-                    existing_balance.component_id, existing_balance.token
-                );
+                if existing_balance.balance == new_balance {
+                    trace!(
+                        component_id = %existing_balance.component_id,
+                        token = %existing_balance.token,
+                        "Received two identical balance updates for the same component and token"
+                    );
+                } else {
+                    warn!(
+                        component_id = %existing_balance.component_id,
+                        token = %existing_balance.token,
+                        previous = %existing_balance.balance,
+                        new = %new_balance,
+                        "Received two balance updates for the same component and token. Overwriting balance change"
+                    );
+                }
             }
         }
 
@@ -346,9 +452,13 @@ impl TryFromMessage for TxWithChanges {
     fn try_from_message(args: Self::Args<'_>) -> Result<Self, ExtractionError> {
         let (msg, block, protocol_system, protocol_types) = args;
         let tx = Transaction::try_from_message((
-            msg.tx
-                .expect("TransactionChanges should have a transaction"),
+            msg.tx.ok_or_else(|| {
+                ExtractionError::DecodeError(
+                    "TransactionChanges should have a transaction".to_owned(),
+                )
+            })?,
             &block.hash.clone(),
+            block.chain,
         ))?;
 
         let mut new_protocol_components: HashMap<ComponentId, ProtocolComponent> = HashMap::new();
@@ -384,15 +494,14 @@ impl TryFromMessage for TxWithChanges {
         // Parse the state updates
         for state_msg in msg.entity_changes.into_iter() {
             let state = ProtocolComponentStateDelta::try_from_message(state_msg)?;
-            // Check if a state update for the same component already exists
-            // If it exists, overwrite the existing state update with the new one and log a warning
+            // If a state update for the same component already exists, merge the new one into it.
+            // Attributes updated by both keep the value of the later update.
             match state_updates.entry(state.component_id.clone()) {
                 Entry::Vacant(e) => {
                     e.insert(state);
                 }
                 Entry::Occupied(mut e) => {
-                    warn!("Received two state updates for the same component. Overwriting state for component {}", e.key());
-                    e.insert(state);
+                    e.get_mut().merge(state)?;
                 }
             }
         }
@@ -469,6 +578,7 @@ impl TryFromMessage for TxWithChanges {
     }
 }
 
+#[allow(deprecated)]
 impl TryFromMessage for BlockContractChanges {
     type Args<'a> = (
         substreams::BlockContractChanges,
@@ -497,7 +607,7 @@ impl TryFromMessage for BlockContractChanges {
                 > = HashMap::new();
 
                 if let Some(tx) = change.tx {
-                    let tx = Transaction::try_from_message((tx, &block.hash.clone()))?;
+                    let tx = Transaction::try_from_message((tx, &block.hash.clone(), block.chain))?;
                     for contract_change in change
                         .contract_changes
                         .clone()
@@ -576,6 +686,7 @@ impl TryFromMessage for BlockContractChanges {
     }
 }
 
+#[allow(deprecated)]
 impl TryFromMessage for BlockEntityChanges {
     type Args<'a> = (
         substreams::BlockEntityChanges,
@@ -634,9 +745,13 @@ impl TryFromMessage for TxWithContractChanges {
     fn try_from_message(args: Self::Args<'_>) -> Result<Self, ExtractionError> {
         let (msg, block) = args;
         let tx = Transaction::try_from_message((
-            msg.tx
-                .expect("TransactionChanges should have a transaction"),
+            msg.tx.ok_or_else(|| {
+                ExtractionError::DecodeError(
+                    "TransactionStorageChanges should have a transaction".to_owned(),
+                )
+            })?,
             &block.hash.clone(),
+            block.chain,
         ))?;
         let mut all_storage_changes = HashMap::new();
         msg.storage_changes
@@ -721,6 +836,7 @@ impl TryFromMessage for BlockChanges {
 mod test {
     use std::str::FromStr;
 
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
     use rstest::rstest;
 
     use super::*;
@@ -790,6 +906,299 @@ mod test {
         assert_eq!(res, exp);
     }
 
+    #[rstest]
+    #[case::seconds(1_700_000_000, true)]
+    #[case::millis(1_700_000_000_000, false)]
+    fn test_parse_block_timestamp(#[case] ts: u64, #[case] valid: bool) {
+        let mut msg = fixtures::pb_blocks(1);
+        msg.ts = ts;
+
+        let res = Block::try_from_message((msg, Chain::Ethereum));
+
+        if valid {
+            assert_eq!(res.unwrap().ts.and_utc().timestamp(), ts as i64);
+        } else {
+            assert!(
+                matches!(res, Err(ExtractionError::DecodeError(msg)) if msg.contains("timestamp"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_block_invalid_hash_length() {
+        let mut msg = fixtures::pb_blocks(1);
+        msg.hash = vec![0x01; 31];
+
+        let res = Block::try_from_message((msg, Chain::Ethereum));
+
+        assert!(matches!(
+            res,
+            Err(ExtractionError::DecodeError(msg)) if msg.contains("block hash") && msg.contains("got 31")
+        ));
+    }
+
+    #[rstest]
+    #[case::hash(vec![0x01; 20], vec![0x02; 20], vec![0x03; 20], "transaction hash")]
+    #[case::from(vec![0x01; 32], vec![0x02; 19], vec![0x03; 20], "from address")]
+    #[case::to(vec![0x01; 32], vec![0x02; 20], vec![0x03; 21], "to address")]
+    fn test_parse_transaction_invalid_lengths(
+        #[case] hash: Vec<u8>,
+        #[case] from: Vec<u8>,
+        #[case] to: Vec<u8>,
+        #[case] field: &str,
+    ) {
+        let msg = substreams::Transaction { hash, from, to, index: 0 };
+
+        let res = Transaction::try_from_message((msg, &Bytes::zero(32), Chain::Ethereum));
+
+        assert!(matches!(res, Err(ExtractionError::DecodeError(msg)) if msg.contains(field)));
+    }
+
+    #[rstest]
+    #[case::with_code(
+        vec![0x60, 0x01],
+        Some(Bytes::from_str("0x309c67890bde4c575dc23d2cc3b5c3a3d599e312e980e9b61b5bc8f3cd87c8bb").unwrap())
+    )]
+    #[case::without_code(vec![], None)]
+    fn test_parse_account_delta_code_hash(#[case] code: Vec<u8>, #[case] expected: Option<Bytes>) {
+        let msg = substreams::ContractChange {
+            address: vec![0x01; 20],
+            code,
+            change: substreams::ChangeType::Update.into(),
+            ..Default::default()
+        };
+
+        let res = AccountDelta::try_from_message((msg, Chain::Ethereum)).unwrap();
+
+        assert_eq!(res.code_hash(), &expected);
+    }
+
+    #[rstest]
+    #[case::ethereum_too_short(Chain::Ethereum, 19, false)]
+    #[case::ethereum(Chain::Ethereum, 20, true)]
+    #[case::starknet_evm_length(Chain::Starknet, 20, false)]
+    #[case::starknet(Chain::Starknet, 32, true)]
+    fn test_parse_account_delta_address_length(
+        #[case] chain: Chain,
+        #[case] length: usize,
+        #[case] valid: bool,
+    ) {
+        let msg = substreams::ContractChange {
+            address: vec![0x01; length],
+            change: substreams::ChangeType::Update.into(),
+            ..Default::default()
+        };
+
+        let res = AccountDelta::try_from_message((msg, chain));
+
+        if valid {
+            assert_eq!(res.unwrap().address, Bytes::from(vec![0x01; length]));
+        } else {
+            assert!(
+                matches!(res, Err(ExtractionError::DecodeError(msg)) if msg.contains("address"))
+            );
+        }
+    }
+
+    #[rstest]
+    #[case::compact(vec![0x01], true)]
+    #[case::word(vec![0x01; 32], true)]
+    #[case::too_long(vec![0x01; 33], false)]
+    fn test_parse_account_delta_slot_length(#[case] slot: Vec<u8>, #[case] valid: bool) {
+        let msg = substreams::ContractChange {
+            address: vec![0x01; 20],
+            slots: vec![substreams::ContractSlot {
+                slot: slot.clone(),
+                value: vec![0x02],
+                ..Default::default()
+            }],
+            change: substreams::ChangeType::Update.into(),
+            ..Default::default()
+        };
+
+        let res = AccountDelta::try_from_message((msg, Chain::Ethereum));
+
+        if valid {
+            assert_eq!(
+                res.unwrap().slots,
+                HashMap::from([(Bytes::from(slot), Some(Bytes::from("0x02")))])
+            );
+        } else {
+            assert!(matches!(res, Err(ExtractionError::DecodeError(msg)) if msg.contains("slot")));
+        }
+    }
+
+    #[test]
+    fn test_parse_tx_changes_merges_state_updates() {
+        let reserve1 = Bytes::from(2_000u64).lpad(32, 0);
+        let reserve3 = Bytes::from(42u64).lpad(32, 0);
+        let msg = substreams::TransactionChanges {
+            tx: Some(fixtures::pb_transactions(1, 0)),
+            entity_changes: vec![
+                fixtures::pb_state_changes(),
+                substreams::EntityChanges {
+                    component_id: "State1".to_owned(),
+                    attributes: vec![
+                        substreams::Attribute {
+                            name: "reserve1".to_owned(),
+                            value: reserve1.to_vec(),
+                            change: substreams::ChangeType::Update.into(),
+                        },
+                        substreams::Attribute {
+                            name: "reserve3".to_owned(),
+                            value: reserve3.to_vec(),
+                            change: substreams::ChangeType::Update.into(),
+                        },
+                    ],
+                },
+            ],
+            ..Default::default()
+        };
+
+        let res =
+            TxWithChanges::try_from_message((msg, &Block::default(), "test", &HashMap::new()))
+                .unwrap();
+
+        let mut expected = fixtures::protocol_state_delta();
+        expected
+            .updated_attributes
+            .insert("reserve1".to_owned(), reserve1);
+        expected
+            .updated_attributes
+            .insert("reserve3".to_owned(), reserve3);
+        assert_eq!(res.state_updates, HashMap::from([("State1".to_owned(), expected)]));
+    }
+
+    #[test]
+    fn test_parse_protocol_changes_merges_state_updates() {
+        let attribute =
+            |name: &str, value: u64, change: substreams::ChangeType| substreams::Attribute {
+                name: name.to_owned(),
+                value: Bytes::from(value).to_vec(),
+                change: change.into(),
+            };
+        let msg = substreams::TransactionEntityChanges {
+            tx: Some(fixtures::pb_transactions(1, 0)),
+            entity_changes: vec![
+                substreams::EntityChanges {
+                    component_id: "State1".to_owned(),
+                    attributes: vec![
+                        attribute("reserve0", 1, substreams::ChangeType::Update),
+                        attribute("reserve1", 1, substreams::ChangeType::Update),
+                        attribute("old0", 0, substreams::ChangeType::Deletion),
+                    ],
+                },
+                substreams::EntityChanges {
+                    component_id: "State1".to_owned(),
+                    attributes: vec![
+                        attribute("reserve1", 2, substreams::ChangeType::Update),
+                        attribute("old1", 0, substreams::ChangeType::Deletion),
+                    ],
+                },
+            ],
+            ..Default::default()
+        };
+
+        let res = ProtocolChangesWithTx::try_from_message((
+            msg,
+            &Block::default(),
+            "test",
+            &HashMap::new(),
+        ))
+        .unwrap();
+
+        let expected = ProtocolComponentStateDelta::new(
+            "State1",
+            HashMap::from([
+                ("reserve0".to_owned(), Bytes::from(1u64)),
+                ("reserve1".to_owned(), Bytes::from(2u64)),
+            ]),
+            HashSet::from(["old0".to_owned(), "old1".to_owned()]),
+        );
+        assert_eq!(res.protocol_states, HashMap::from([("State1".to_owned(), expected)]));
+    }
+
+    #[rstest]
+    #[case::identical(1_000, 1_000)]
+    #[case::differing(1_000, 2_000)]
+    fn test_parse_protocol_changes_duplicate_balances(#[case] first: u64, #[case] second: u64) {
+        let token = Bytes::from_str("0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2").unwrap();
+        let balance_change = |balance: u64| substreams::BalanceChange {
+            balance: Bytes::from(balance).to_vec(),
+            token: token.to_vec(),
+            component_id: b"State1".to_vec(),
+        };
+        let msg = substreams::TransactionEntityChanges {
+            tx: Some(fixtures::pb_transactions(1, 0)),
+            balance_changes: vec![balance_change(first), balance_change(second)],
+            ..Default::default()
+        };
+
+        let res = ProtocolChangesWithTx::try_from_message((
+            msg,
+            &Block::default(),
+            "test",
+            &HashMap::new(),
+        ))
+        .unwrap();
+
+        let balances = &res.balance_changes["State1"];
+        assert_eq!(balances.len(), 1);
+        assert_eq!(balances[&token].balance, Bytes::from(second));
+    }
+
+    #[test]
+    fn test_parse_tx_changes_deleted_component() {
+        let mut component = fixtures::pb_protocol_component();
+        component.change = substreams::ChangeType::Deletion.into();
+        let component_id = component.id.clone();
+        let msg = substreams::TransactionChanges {
+            tx: Some(fixtures::pb_transactions(1, 0)),
+            component_changes: vec![component],
+            ..Default::default()
+        };
+        let protocol_types = HashMap::from([("WeightedPool".to_string(), ProtocolType::default())]);
+
+        let res =
+            TxWithChanges::try_from_message((msg, &Block::default(), "test", &protocol_types))
+                .unwrap();
+
+        assert_eq!(res.protocol_components[&component_id].change, ChangeType::Deletion);
+    }
+
+    #[test]
+    fn test_parse_tx_changes_without_tx() {
+        let msg = substreams::TransactionChanges { tx: None, ..Default::default() };
+
+        let res =
+            TxWithChanges::try_from_message((msg, &Block::default(), "test", &HashMap::new()));
+
+        assert!(matches!(res, Err(ExtractionError::DecodeError(_))));
+    }
+
+    #[test]
+    fn test_parse_protocol_changes_without_tx() {
+        let msg = substreams::TransactionEntityChanges { tx: None, ..Default::default() };
+
+        let res = ProtocolChangesWithTx::try_from_message((
+            msg,
+            &Block::default(),
+            "test",
+            &HashMap::new(),
+        ));
+
+        assert!(matches!(res, Err(ExtractionError::DecodeError(_))));
+    }
+
+    #[test]
+    fn test_parse_tx_with_storage_changes_without_tx() {
+        let msg = substreams::TransactionStorageChanges { tx: None, ..Default::default() };
+
+        let res = TxWithContractChanges::try_from_message((msg, &Block::default()));
+
+        assert!(matches!(res, Err(ExtractionError::DecodeError(_))));
+    }
+
     #[test]
     fn test_parse_protocol_component() {
         let msg = fixtures::pb_protocol_component();
@@ -884,6 +1293,44 @@ mod test {
     }
 
     #[test]
+    fn test_parse_component_balance_invalid_encoding() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let msg = substreams::BalanceChange {
+            token: vec![0x01; 20],
+            balance: vec![0x01; 33],
+            component_id: "pool".as_bytes().to_vec(),
+        };
+        let tx = transaction();
+
+        let res = metrics::with_local_recorder(&recorder, || {
+            ComponentBalance::try_from_message((msg, &tx)).unwrap()
+        });
+
+        assert!(res.balance_float.is_nan());
+        let invalid_count = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .find(|(key, _, _, _)| key.key().name() == "extractor_invalid_component_balances")
+            .map(|(_, _, _, value)| value);
+        assert_eq!(invalid_count, Some(DebugValue::Counter(1)));
+    }
+
+    #[test]
+    fn test_balance_to_f64_strict() {
+        let balance = Bytes::from(vec![0x01; 33]);
+
+        let res = balance_to_f64("pool", &balance, true);
+
+        assert!(matches!(res, Err(ExtractionError::DecodeError(msg)) if msg.contains("pool")));
+        assert!(balance_to_f64("pool", &balance, false)
+            .unwrap()
+            .is_nan());
+    }
+
+    #[test]
+    #[allow(deprecated)]
     fn test_parse_block_contract_changes() {
         let msg = fixtures::pb_block_contract_changes(0);
 
@@ -900,6 +1347,7 @@ mod test {
     }
 
     #[test]
+    #[allow(deprecated)]
     fn test_block_entity_changes_parse_msg() {
         let msg = fixtures::pb_block_entity_changes(0);
 
@@ -953,4 +1401,17 @@ mod test {
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn test_parse_entrypoint_params_without_trace_data() {
+        let msg = substreams::EntryPointParams {
+            entrypoint_id: "test_entrypoint".to_string(),
+            component_id: Some("test_component".to_string()),
+            trace_data: None,
+        };
+
+        let result = TracingParams::try_from_message(msg);
+
+        assert!(matches!(result, Err(ExtractionError::DecodeError(_))));
+    }
 }
@@ -4,10 +4,10 @@ use tycho_common::dto::{
     PaginationParams, PaginationResponse, ProtocolComponent, ProtocolComponentRequestResponse,
     ProtocolComponentsRequestBody, ProtocolId, ProtocolStateDelta, ProtocolStateRequestBody,
     ProtocolStateRequestResponse, ProtocolSystemsRequestBody, ProtocolSystemsRequestResponse,
-    RPCTracerParams, ResponseAccount, ResponseProtocolState, ResponseToken, StateRequestBody,
-    StateRequestResponse, StorageOverride, TokensRequestBody, TokensRequestResponse,
-    TracedEntryPointRequestBody, TracedEntryPointRequestResponse, TracingParams, TracingResult,
-    VersionParam,
+    RPCTracerParams, ResponseAccount, ResponseProtocolState, ResponseToken, RpcErrorResponse,
+    StateRequestBody, StateRequestResponse, StorageOverride, TokensRequestBody,
+    TokensRequestResponse, TracedEntryPointRequestBody, TracedEntryPointRequestResponse,
+    TracingParams, TracingResult, VersionParam,
 };
 use utoipa::{
     openapi::security::{ApiKey, ApiKeyValue, SecurityScheme},
@@ -36,12 +36,14 @@ impl Modify for SecurityAddon {
     info(title = "Tycho-Indexer RPC",),
     paths(
         rpc::health,
+        rpc::ready,
         rpc::protocol_systems,
         rpc::tokens,
         rpc::protocol_components,
         rpc::traced_entry_points,
         rpc::protocol_state,
         rpc::contract_state,
+        rpc::contract_state_query,
         rpc::component_tvl,
     ),
     components(
@@ -70,6 +72,7 @@ impl Modify for SecurityAddon {
         schemas(ChangeType),
         schemas(ProtocolStateDelta),
         schemas(Health),
+        schemas(RpcErrorResponse),
         schemas(ProtocolSystemsRequestBody),
         schemas(ProtocolSystemsRequestResponse),
         schemas(ComponentTvlRequestBody),
@@ -132,4 +135,19 @@ mod tests {
             missing
         );
     }
+
+    #[test]
+    fn test_openapi_contract_state_get_uses_query_params() {
+        let doc = ApiDoc::openapi();
+        let json =
+            serde_json::to_value(&doc).expect("OpenAPI document should serialize to JSON value");
+        let params = json["paths"]["/v1/contract_state"]["get"]["parameters"]
+            .as_array()
+            .expect("GET /v1/contract_state should declare parameters");
+
+        assert!(!params.is_empty());
+        for param in params {
+            assert_eq!(param["in"], "query", "unexpected parameter location: {param}");
+        }
+    }
 }
@@ -125,7 +125,7 @@ pub trait ValidateFilter {
     /// Validates the filter parameters against the provided RPC configuration.
     ///
     /// # Errors
-    /// Returns `RpcError::MinimumFilterNotMet` if the request doesn't meet filtering requirements.
+    /// Returns an `RpcError` if the request doesn't meet filtering requirements.
     fn validate_filter(&self, config: &ServerRpcConfig) -> Result<(), RpcError>;
 }
 
@@ -231,6 +231,47 @@ impl ValidateFilter for dto::TokensRequestBody {
     }
 }
 
+/// Validation implementation for contract state requests.
+///
+/// Contract state requests carry no TVL or inertia filters, so `contract_ids` is the only way to
+/// narrow them. When `require_contract_ids` is configured, clients must provide a non-empty
+/// `contract_ids`. When `max_contract_accounts` is configured, a request may return at most that
+/// many accounts: either through its `contract_ids`, or through its page when no ids are given.
+impl ValidateFilter for dto::StateRequestBody {
+    fn validate_filter(&self, config: &ServerRpcConfig) -> Result<(), RpcError> {
+        let ids = self
+            .contract_ids
+            .as_ref()
+            .filter(|ids| !ids.is_empty());
+
+        if config.require_contract_ids() && ids.is_none() {
+            return Err(RpcError::Parse(
+                "contract_ids parameter is required to prevent overly broad queries.".to_string(),
+            ));
+        }
+
+        if let Some(max_accounts) = config.max_contract_accounts() {
+            let requested = match ids {
+                Some(ids) => ids.len(),
+                None => self
+                    .pagination
+                    .page
+                    .saturating_add(1)
+                    .saturating_mul(self.pagination.page_size)
+                    .max(0) as usize,
+            };
+            if requested > max_accounts {
+                return Err(RpcError::Parse(format!(
+                    "contract_state returns at most {} accounts (requested: {}).",
+                    max_accounts, requested
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::{BTreeMap, HashMap};
@@ -749,4 +790,86 @@ mod tests {
             }
         }
     }
+
+    #[rstest]
+    #[case::accepts_none_by_default(false, None, None, 0, 10, None)]
+    #[case::rejects_none_when_required(
+        true,
+        None,
+        None,
+        0,
+        10,
+        Some("contract_ids parameter is required")
+    )]
+    #[case::rejects_empty_when_required(
+        true,
+        None,
+        Some(vec![]),
+        0,
+        10,
+        Some("contract_ids parameter is required")
+    )]
+    #[case::accepts_ids_when_required(true, None, Some(vec![Bytes::from("0x01")]), 0, 10, None)]
+    #[case::rejects_ids_above_max(
+        false,
+        Some(2),
+        Some(vec![Bytes::from("0x01"), Bytes::from("0x02"), Bytes::from("0x03")]),
+        0,
+        10,
+        Some("at most 2 accounts (requested: 3)")
+    )]
+    #[case::accepts_ids_up_to_max(
+        false,
+        Some(2),
+        Some(vec![Bytes::from("0x01"), Bytes::from("0x02")]),
+        0,
+        10,
+        None
+    )]
+    #[case::accepts_pages_up_to_max(false, Some(20), None, 1, 10, None)]
+    #[case::rejects_pages_above_max(
+        false,
+        Some(20),
+        None,
+        2,
+        10,
+        Some("at most 20 accounts (requested: 30)")
+    )]
+    #[tokio::test]
+    async fn test_contract_state_validation(
+        #[case] require_contract_ids: bool,
+        #[case] max_contract_accounts: Option<usize>,
+        #[case] contract_ids: Option<Vec<Bytes>>,
+        #[case] page: i64,
+        #[case] page_size: i64,
+        #[case] error_message_contains: Option<&str>,
+    ) {
+        let config = ServerRpcConfig::new()
+            .with_require_contract_ids(require_contract_ids)
+            .with_max_contract_accounts(max_contract_accounts);
+
+        let request = dto::StateRequestBody {
+            contract_ids,
+            protocol_system: "uniswap_v2".to_string(),
+            version: dto::VersionParam::default(),
+            chain: dto::Chain::Ethereum,
+            pagination: dto::PaginationParams::new(page, page_size),
+        };
+
+        let result = request.validate_filter(&config);
+
+        match error_message_contains {
+            None => assert!(result.is_ok(), "Expected success but got error: {:?}", result),
+            Some(msg) => {
+                let err = result.unwrap_err();
+                assert!(matches!(err, RpcError::Parse(_)));
+                assert!(
+                    err.to_string().contains(msg),
+                    "Error message '{}' does not contain '{}'",
+                    err,
+                    msg
+                );
+            }
+        }
+    }
 }
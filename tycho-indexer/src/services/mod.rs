@@ -203,7 +203,8 @@ where
                 .app_data(rpc_data.clone())
                 .service(
                     web::resource(format!("/{}/contract_state", self.prefix))
-                        .route(web::post().to(rpc::contract_state::<G, EVMEntrypointService>)),
+                        .route(web::post().to(rpc::contract_state::<G, EVMEntrypointService>))
+                        .route(web::get().to(rpc::contract_state_query::<G, EVMEntrypointService>)),
                 )
                 .service(
                     web::resource(format!("/{}/protocol_state", self.prefix))
@@ -231,6 +232,12 @@ where
                     web::resource(format!("/{}/health", self.prefix))
                         .route(web::get().to(rpc::health)),
                 )
+                // Unversioned liveness and readiness probes
+                .service(web::resource("/health").route(web::get().to(rpc::health)))
+                .service(
+                    web::resource("/ready")
+                        .route(web::get().to(rpc::ready::<G, EVMEntrypointService>)),
+                )
                 .service(
                     web::resource(format!("/{}/protocol_systems", self.prefix))
                         .route(web::post().to(rpc::protocol_systems::<G, EVMEntrypointService>)),
@@ -285,11 +292,24 @@ pub struct ServerRpcConfig {
     /// When set, clients must provide a `traded_n_days_ago` parameter at most this value,
     /// unless querying specific `token_addresses`.
     max_traded_n_days_ago: Option<u64>,
+    /// Whether contract state requests must provide `contract_ids`.
+    /// When unset, requests without ids return every contract on the chain.
+    require_contract_ids: bool,
+    /// Maximum number of accounts a contract state request may return.
+    /// When set, requests for more `contract_ids`, or pages reaching past this many accounts when
+    /// no ids are given, are rejected.
+    max_contract_accounts: Option<usize>,
 }
 
 impl ServerRpcConfig {
     pub fn new() -> Self {
-        Self { min_component_tvl: None, min_token_quality: None, max_traded_n_days_ago: None }
+        Self {
+            min_component_tvl: None,
+            min_token_quality: None,
+            max_traded_n_days_ago: None,
+            require_contract_ids: false,
+            max_contract_accounts: None,
+        }
     }
 
     pub fn with_min_tvl(mut self, min_tvl: Option<f64>) -> Self {
@@ -307,6 +327,16 @@ impl ServerRpcConfig {
         self
     }
 
+    pub fn with_require_contract_ids(mut self, require_contract_ids: bool) -> Self {
+        self.require_contract_ids = require_contract_ids;
+        self
+    }
+
+    pub fn with_max_contract_accounts(mut self, max_contract_accounts: Option<usize>) -> Self {
+        self.max_contract_accounts = max_contract_accounts;
+        self
+    }
+
     pub fn min_component_tvl(&self) -> Option<f64> {
         self.min_component_tvl
     }
@@ -318,4 +348,12 @@ impl ServerRpcConfig {
     pub fn max_traded_n_days_ago(&self) -> Option<u64> {
         self.max_traded_n_days_ago
     }
+
+    pub fn require_contract_ids(&self) -> bool {
+        self.require_contract_ids
+    }
+
+    pub fn max_contract_accounts(&self) -> Option<usize> {
+        self.max_contract_accounts
+    }
 }
@@ -66,12 +66,29 @@ impl From<anyhow::Error> for RpcError {
     }
 }
 
+impl RpcError {
+    /// Machine readable code identifying the kind of error, returned in the error body.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RpcError::Storage(StorageError::NotFound(..)) => "not_found",
+            RpcError::Storage(_) => "storage_error",
+            RpcError::Parse(_) => "parse_error",
+            RpcError::Connection(_) => "connection_error",
+            RpcError::DeltasError(_) => "deltas_error",
+            RpcError::Pagination(_) => "pagination_error",
+            RpcError::Unknown(_) => "unknown_error",
+            RpcError::MinimumFilterNotMet(_, _) => "minimum_filter_not_met",
+        }
+    }
+}
+
 impl ResponseError for RpcError {
     fn status_code(&self) -> StatusCode {
         match self {
-            RpcError::Storage(_) => StatusCode::NOT_FOUND,
+            RpcError::Storage(StorageError::NotFound(..)) => StatusCode::NOT_FOUND,
+            RpcError::Storage(_) => StatusCode::INTERNAL_SERVER_ERROR,
             RpcError::Parse(_) => StatusCode::BAD_REQUEST,
-            RpcError::Connection(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            RpcError::Connection(_) => StatusCode::SERVICE_UNAVAILABLE,
             RpcError::DeltasError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             RpcError::Pagination(_) => StatusCode::BAD_REQUEST,
             RpcError::Unknown(_) => StatusCode::INTERNAL_SERVER_ERROR,
@@ -80,19 +97,23 @@ impl ResponseError for RpcError {
     }
 
     fn error_response(&self) -> HttpResponse {
-        match self {
-            RpcError::Storage(e) => HttpResponse::NotFound().body(e.to_string()),
-            RpcError::Parse(e) => HttpResponse::BadRequest().body(e.to_string()),
-            RpcError::Connection(e) => HttpResponse::InternalServerError().body(e.to_string()),
-            RpcError::DeltasError(e) => HttpResponse::InternalServerError().body(e.to_string()),
-            RpcError::Pagination(e) => HttpResponse::BadRequest()
-                .body(format!("Page size must be less than or equal to {e}.")),
-            RpcError::Unknown(e) => HttpResponse::InternalServerError().body(e.to_string()),
-            RpcError::MinimumFilterNotMet(_, e) => HttpResponse::BadRequest().body(e.to_owned()),
-        }
+        let message = match self {
+            RpcError::Storage(e) => e.to_string(),
+            RpcError::Parse(e) => e.to_string(),
+            RpcError::Connection(e) => e.to_string(),
+            RpcError::DeltasError(e) => e.to_string(),
+            RpcError::Pagination(e) => format!("Page size must be less than or equal to {e}."),
+            RpcError::Unknown(e) => e.to_string(),
+            RpcError::MinimumFilterNotMet(_, e) => e.to_owned(),
+        };
+        HttpResponse::build(self.status_code())
+            .json(dto::RpcErrorResponse::new(self.code(), message))
     }
 }
 
+/// Maximum time the readiness check waits for the database to respond.
+const READY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
 pub struct RpcHandler<G, T> {
     db_gateway: G,
     // TODO: remove use of Arc. It was introduced for ease of testing this deltas buffer, however
@@ -388,6 +409,15 @@ where
         }
     }
 
+    /// Checks that a database connection can be acquired and queried within [`READY_TIMEOUT`].
+    async fn check_db_ready(&self) -> Result<(), String> {
+        match tokio::time::timeout(READY_TIMEOUT, self.db_gateway.check_connection()).await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(err)) => Err(format!("Database connection failed: {err}")),
+            Err(_) => Err("Database connection timed out".to_string()),
+        }
+    }
+
     #[instrument(skip(self, request))]
     async fn get_protocol_state(
         &self,
@@ -1074,12 +1104,13 @@ where
 /// Retrieve contract states
 ///
 /// This endpoint retrieves the state of contracts within a specific execution environment. If no
-/// contract ids are given, all contracts are returned. Note that `protocol_system` is not a filter;
-/// it's a way to specify the protocol system associated with the contracts requested and is used to
-/// ensure that the correct extractor's block status is used when querying the database. If omitted,
-/// the block status will be determined by a random extractor, which could be risky if the extractor
-/// is out of sync. Filtering by protocol system is not currently supported on this endpoint and
-/// should be done client side.
+/// contract ids are given, all contracts are returned, unless the server is configured to require
+/// contract ids. Note that `protocol_system` is not a filter; it's a way to specify the protocol
+/// system associated with the contracts requested and is used to ensure that the correct
+/// extractor's block status is used when querying the database. If omitted, the block status will
+/// be determined by a random extractor, which could be risky if the extractor is out of sync.
+/// Filtering by protocol system is not currently supported on this endpoint and should be done
+/// client side.
 #[utoipa::path(
     post,
     path = "/v1/contract_state",
@@ -1096,6 +1127,41 @@ pub async fn contract_state<G: Gateway, T: EntryPointTracer>(
     req: actix_web::HttpRequest,
     body: web::Json<dto::StateRequestBody>,
     handler: web::Data<RpcHandler<G, T>>,
+) -> Result<HttpResponse, RpcError> {
+    contract_state_response(req, body.into_inner(), handler).await
+}
+
+/// Retrieve contract states using query parameters
+///
+/// Same as the POST variant of this endpoint, for clients that can't send a request body.
+/// Contract ids are passed as a comma separated list and the version as a block number, block hash
+/// or timestamp.
+#[utoipa::path(
+    get,
+    path = "/v1/contract_state",
+    responses(
+        (status = 200, description = "OK", body = StateRequestResponse),
+    ),
+    params(dto::StateRequestQuery),
+    security(
+         ("apiKey" = [])
+    ),
+)]
+#[instrument(skip_all, fields(page, page_size, protocol_system))]
+pub async fn contract_state_query<G: Gateway, T: EntryPointTracer>(
+    req: actix_web::HttpRequest,
+    query: web::Query<dto::StateRequestQuery>,
+    handler: web::Data<RpcHandler<G, T>>,
+) -> Result<HttpResponse, RpcError> {
+    let body = dto::StateRequestBody::try_from(query.into_inner())
+        .map_err(|err| RpcError::Parse(err.to_string()))?;
+    contract_state_response(req, body, handler).await
+}
+
+async fn contract_state_response<G: Gateway, T: EntryPointTracer>(
+    req: actix_web::HttpRequest,
+    body: dto::StateRequestBody,
+    handler: web::Data<RpcHandler<G, T>>,
 ) -> Result<HttpResponse, RpcError> {
     // Note - filtering by protocol system is not supported on this endpoint. This is due to the
     // complexity of paginating this endpoint with the current design.
@@ -1106,6 +1172,7 @@ pub async fn contract_state<G: Gateway, T: EntryPointTracer>(
     tracing::Span::current().record("protocol_system", &body.protocol_system);
 
     body.validate_pagination(&req)?;
+    body.validate_filter(&handler.rpc_config)?;
 
     // Call the handler to get the state
     let response = handler
@@ -1425,6 +1492,30 @@ pub async fn health() -> Result<HttpResponse, RpcError> {
     Ok(HttpResponse::Ok().json(dto::Health::Ready))
 }
 
+/// Readiness check endpoint
+///
+/// This endpoint is used to check whether the service can serve requests. It returns 503 if the
+/// database can't be reached.
+#[utoipa::path(
+    get,
+    path = "/ready",
+    responses(
+        (status = 200, description = "OK", body=Health),
+        (status = 503, description = "Database unreachable", body=Health),
+    ),
+)]
+pub async fn ready<G: Gateway, T: EntryPointTracer>(
+    handler: web::Data<RpcHandler<G, T>>,
+) -> Result<HttpResponse, RpcError> {
+    match handler.check_db_ready().await {
+        Ok(()) => Ok(HttpResponse::Ok().json(dto::Health::Ready)),
+        Err(reason) => {
+            warn!(%reason, "Readiness check failed.");
+            Ok(HttpResponse::ServiceUnavailable().json(dto::Health::NotReady(reason)))
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::{collections::HashMap, env, str::FromStr};
@@ -1689,6 +1780,65 @@ mod tests {
         assert_eq!(state.pagination.total, 2);
     }
 
+    #[tokio::test]
+    async fn test_get_contract_state_zksync() {
+        let account = Account::new(
+            Chain::ZkSync,
+            Bytes::from_str(WETH).unwrap(),
+            "weth".to_owned(),
+            HashMap::new(),
+            Bytes::from(101u8).lpad(32, 0),
+            HashMap::new(),
+            Bytes::from("C0C0C0"),
+            Bytes::zero(32),
+            Bytes::zero(32),
+            Bytes::zero(32),
+            None,
+        );
+        let mut gw = MockGateway::new();
+        let mock_response = Ok(WithTotal { entity: vec![account.clone()], total: Some(1) });
+        gw.expect_get_contracts()
+            .withf(|chain, _, version, _, _| {
+                chain == &Chain::ZkSync &&
+                    matches!(
+                        version,
+                        Some(Version(
+                            BlockOrTimestamp::Block(BlockIdentifier::Latest(Chain::ZkSync)),
+                            _
+                        ))
+                    )
+            })
+            .return_once(|_, _, _, _, _| Box::pin(async move { mock_response }));
+        let mut mock_buffer = MockPendingDeltas::new();
+        mock_buffer
+            .expect_get_block_commit_status()
+            .return_once(|_, _| Ok(Some(CommitStatus::Uncommitted)));
+        mock_buffer
+            .expect_update_vm_states()
+            .return_once(|_, _, _, _| Ok(()));
+        let req_handler = RpcHandler::new(
+            gw,
+            Some(Arc::new(mock_buffer)),
+            MockEntryPointTracer::new(),
+            ServerRpcConfig::new(),
+        );
+        let request = dto::StateRequestBody {
+            contract_ids: Some(vec![Bytes::from_str(WETH).unwrap()]),
+            protocol_system: "uniswap_v2".to_string(),
+            version: dto::VersionParam { timestamp: Some(Utc::now().naive_utc()), block: None },
+            chain: dto::Chain::ZkSync,
+            pagination: dto::PaginationParams::default(),
+        };
+
+        let state = req_handler
+            .get_contract_state_inner(request)
+            .await
+            .unwrap();
+
+        assert_eq!(state.accounts, vec![dto::ResponseAccount::from(account)]);
+        assert_eq!(state.accounts[0].chain, dto::Chain::ZkSync);
+    }
+
     /// Helper used to make tracing results comparisons deterministic.
     #[allow(clippy::type_complexity)]
     fn normalize_tracing_result(
@@ -2869,4 +3019,117 @@ mod tests {
             );
         }
     }
+
+    #[rstest]
+    #[case::parse(RpcError::Parse("bad block".to_string()), StatusCode::BAD_REQUEST, "bad block")]
+    #[case::not_found(
+        RpcError::Storage(StorageError::NotFound("Contract".to_string(), "0x01".to_string())),
+        StatusCode::NOT_FOUND,
+        "Could not find Contract"
+    )]
+    #[case::storage_failure(
+        RpcError::Storage(StorageError::Unexpected("db down".to_string())),
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "db down"
+    )]
+    #[case::connection(
+        RpcError::Connection(deadpool::PoolError::Closed),
+        StatusCode::SERVICE_UNAVAILABLE,
+        "closed"
+    )]
+    #[tokio::test]
+    async fn test_rpc_error_response(
+        #[case] error: RpcError,
+        #[case] expected_status: StatusCode,
+        #[case] expected_message: &str,
+    ) {
+        let resp = error.error_response();
+
+        assert_eq!(error.status_code(), expected_status);
+        assert_eq!(resp.status(), expected_status);
+        let body = actix_web::body::to_bytes(resp.into_body())
+            .await
+            .unwrap();
+        let body: dto::RpcErrorResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body.code, error.code());
+        assert!(body.message.contains(expected_message), "Unexpected message: {}", body.message);
+    }
+
+    #[rstest]
+    #[case::db_reachable(true, actix_web::http::StatusCode::OK)]
+    #[case::db_unreachable(false, actix_web::http::StatusCode::SERVICE_UNAVAILABLE)]
+    #[actix_web::test]
+    async fn test_ready_endpoint(
+        #[case] db_reachable: bool,
+        #[case] expected_status: actix_web::http::StatusCode,
+    ) {
+        let mut gw = MockGateway::new();
+        gw.expect_check_connection()
+            .return_once(move || {
+                if db_reachable {
+                    Ok(())
+                } else {
+                    Err(StorageError::Unexpected("connection refused".to_string()))
+                }
+            });
+        let handler =
+            RpcHandler::new(gw, None, MockEntryPointTracer::new(), ServerRpcConfig::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(handler))
+                .route("/ready", web::get().to(ready::<MockGateway, MockEntryPointTracer>)),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri("/ready")
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+
+        assert_eq!(resp.status(), expected_status);
+    }
+
+    #[actix_web::test]
+    async fn test_contract_state_query_endpoint() {
+        let account = Account::new(
+            Chain::Ethereum,
+            Bytes::from_str(WETH).unwrap(),
+            "weth".to_owned(),
+            HashMap::new(),
+            Bytes::from(101u8).lpad(32, 0),
+            HashMap::new(),
+            Bytes::from("C0C0C0"),
+            Bytes::zero(32),
+            Bytes::zero(32),
+            Bytes::zero(32),
+            None,
+        );
+        let mut gw = MockGateway::new();
+        let mock_response = Ok(WithTotal { entity: vec![account.clone()], total: Some(1) });
+        gw.expect_get_contracts()
+            .withf(|chain, ids, _, _, _| {
+                chain == &Chain::Ethereum &&
+                    ids.is_some_and(|ids| ids == [Bytes::from_str(WETH).unwrap()])
+            })
+            .return_once(|_, _, _, _, _| Box::pin(async move { mock_response }));
+        let handler =
+            RpcHandler::new(gw, None, MockEntryPointTracer::new(), ServerRpcConfig::new());
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(handler))
+                .route(
+                    "/v1/contract_state",
+                    web::get().to(contract_state_query::<MockGateway, MockEntryPointTracer>),
+                ),
+        )
+        .await;
+
+        let req = test::TestRequest::get()
+            .uri(&format!("/v1/contract_state?contract_ids={WETH}&protocol_system=uniswap_v2"))
+            .to_request();
+        let resp: dto::StateRequestResponse = test::call_and_read_body_json(&app, req).await;
+
+        assert_eq!(resp.accounts, vec![dto::ResponseAccount::from(account)]);
+        assert_eq!(resp.pagination.total, 1);
+    }
 }
@@ -648,7 +648,10 @@ mock! {
             Self: 'async_trait;
     }
 
-    impl Gateway for Gateway {}
+    #[async_trait]
+    impl Gateway for Gateway {
+        async fn check_connection(&self) -> Result<(), StorageError>;
+    }
 }
 
 #[cfg(test)]
@@ -1266,7 +1266,12 @@ impl EntryPointGateway for CachedGateway {
     }
 }
 
-impl Gateway for CachedGateway {}
+#[async_trait]
+impl Gateway for CachedGateway {
+    async fn check_connection(&self) -> Result<(), StorageError> {
+        super::check_connection(&self.pool).await
+    }
+}
 
 #[cfg(test)]
 mod test_serial_db {
@@ -692,4 +692,9 @@ impl EntryPointGateway for DirectGateway {
     }
 }
 
-impl Gateway for DirectGateway {}
+#[async_trait]
+impl Gateway for DirectGateway {
+    async fn check_connection(&self) -> Result<(), StorageError> {
+        super::check_connection(&self.pool).await
+    }
+}
@@ -607,6 +607,19 @@ async fn connect(db_url: &str) -> Result<Pool<AsyncPgConnection>, StorageError>
     Ok(pool)
 }
 
+/// Acquires a connection from the pool and runs a trivial query on it.
+async fn check_connection(pool: &Pool<AsyncPgConnection>) -> Result<(), StorageError> {
+    let mut conn = pool
+        .get()
+        .await
+        .map_err(|e| StorageError::Unexpected(format!("Failed to retrieve connection: {e}")))?;
+    diesel::sql_query("SELECT 1")
+        .execute(&mut conn)
+        .await
+        .map_err(PostgresError::from)?;
+    Ok(())
+}
+
 /// Ensures the `Chain` enum is present in the database, if not it inserts it.
 ///
 /// This function serves as a way to ensure all chains found within the `chains`  